@@ -0,0 +1,116 @@
+//! Deprecated names of functions which have been renamed to match Sprig.
+//!
+//! Both names stay registered in `SPRIG`. The deprecated one logs a warning
+//! (once per process) pointing to the new name the first time it is called.
+//! `Builder` selects the deprecated names along with the new ones.
+
+use std::any::Any;
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+
+use strings;
+
+/// Deprecated names with the names replacing them.
+pub const ALIASES: &[(&str, &str)] = &[
+    ("base32decode", "b32dec"),
+    ("base32encode", "b32enc"),
+    ("base64decode", "b64dec"),
+    ("base64encode", "b64enc"),
+    ("substring", "substr"),
+];
+
+lazy_static! {
+    static ref WARNED: Mutex<HashSet<&'static str>> = Mutex::new(HashSet::new());
+}
+
+/// The current name of a function, which is `name` itself unless it is
+/// deprecated.
+pub fn canonical_name(name: &str) -> &str {
+    ALIASES
+        .iter()
+        .find(|&&(old, _)| old == name)
+        .map_or(name, |&(_, new)| new)
+}
+
+/// Log a warning that `old` is deprecated in favour of `new`, unless that
+/// has been done before. Returns whether it warned.
+fn warn_deprecated(old: &'static str, new: &'static str) -> bool {
+    let first = WARNED.lock().unwrap().insert(old);
+    if first {
+        warn!("sprig: {} is deprecated, use {} instead", old, new);
+    }
+    first
+}
+
+macro_rules! deprecated_fn {
+    ($(#[$outer:meta])* $name:ident, $new:expr, $func:path) => {
+        $(#[$outer])*
+        pub fn $name(args: &[Arc<Any>]) -> Result<Arc<Any>, String> {
+            warn_deprecated(stringify!($name), $new);
+            $func(args)
+        }
+    }
+}
+
+deprecated_fn!(
+    /// Deprecated name of `b32dec`.
+    base32decode, "b32dec", strings::base32decode
+);
+deprecated_fn!(
+    /// Deprecated name of `b32enc`.
+    base32encode, "b32enc", strings::base32encode
+);
+deprecated_fn!(
+    /// Deprecated name of `b64dec`.
+    base64decode, "b64dec", strings::base64decode
+);
+deprecated_fn!(
+    /// Deprecated name of `b64enc`.
+    base64encode, "b64enc", strings::base64encode
+);
+deprecated_fn!(
+    /// Deprecated name of `substr`.
+    substring, "substr", strings::substring
+);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::any::Any;
+    use std::sync::Arc;
+    use gtmpl_value::Value;
+    use SPRIG;
+
+    #[test]
+    fn test_canonical_name() {
+        assert_eq!(canonical_name("substring"), "substr");
+        assert_eq!(canonical_name("base64encode"), "b64enc");
+        assert_eq!(canonical_name("substr"), "substr");
+        assert_eq!(canonical_name("upper"), "upper");
+    }
+
+    #[test]
+    fn test_aliases_registered() {
+        for &(old, new) in ALIASES {
+            assert!(SPRIG.contains_key(old), "{} is not registered", old);
+            assert!(SPRIG.contains_key(new), "{} is not registered", new);
+        }
+    }
+
+    #[test]
+    fn test_alias_resolves() {
+        test_fn!(base64encode, vvarc!("foo"), "Zm9v");
+        test_fn!(substring, vvarc!(1, 4, "foobar"), "oob");
+        let b64enc = SPRIG["b64enc"];
+        let ret = b64enc(&vvarc!("foo")).unwrap();
+        assert_eq!(ret.downcast_ref::<Value>(), Some(&Value::from("Zm9v")));
+    }
+
+    #[test]
+    fn test_warn_once() {
+        test_fn!(base64encode, vvarc!("foo"), "Zm9v");
+        test_fn!(base64encode, vvarc!("foo"), "Zm9v");
+        assert!(WARNED.lock().unwrap().contains("base64encode"));
+        assert!(!warn_deprecated("base64encode", "b64enc"));
+    }
+}
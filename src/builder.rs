@@ -0,0 +1,389 @@
+//! Selective registration of sprig functions, e.g. to only expose a few
+//! harmless helpers to untrusted templates:
+//!
+//! ```ignore
+//! let funcs = Builder::new().with_strings().with_math().without("randInt").build();
+//! ```
+
+use std::collections::{BTreeSet, HashMap};
+
+use gtmpl::Func;
+use gtmpl_value::Value;
+
+use aliases;
+use tpl;
+use SPRIG;
+
+const STRINGS: &[&str] = &[
+    "split",
+    "substr",
+    "matchesGlob",
+    "trimQuotes",
+    "titleWords",
+    "untitleWords",
+    "wordCount",
+    "charCount",
+    "reverse",
+    "capitalize",
+    "uncapitalize",
+    "isBlank",
+    "coalesceStr",
+    "trimReport",
+    "substrNeg",
+    "repeatSep",
+    "splitList",
+    "lastIndexOf",
+    "splitLines",
+    "wrap",
+    "wrapWith",
+    "dedent",
+    "reindent",
+    "indent",
+    "nindent",
+    "indentTabs",
+    "nindentTabs",
+    "snakecase",
+    "kebabcase",
+    "camelcase",
+    "upper",
+    "lower",
+    "title",
+    "swapcase",
+    "repeat",
+    "nospace",
+    "quote",
+    "squote",
+    "cat",
+    "printf",
+    "humanizeBytes",
+    "humanizeDuration",
+    "ordinal",
+    "commaize",
+    "commaizeFloat",
+    "romanize",
+    "deromanize",
+];
+
+const MATH: &[&str] = &[
+    "add",
+    "add1",
+    "sub",
+    "mul",
+    "div",
+    "max",
+    "biggest",
+    "min",
+    "mod",
+    "pow",
+    "sqrt",
+    "floor",
+    "ceil",
+    "round",
+    "randInt",
+    "until",
+    "untilStep",
+    "gcd",
+    "lcm",
+    "clamp",
+];
+
+const LISTS: &[&str] = &[
+    "list",
+    "randChoice",
+    "shuffleList",
+    "sample",
+    "first",
+    "last",
+    "rest",
+    "initial",
+    "append",
+    "prepend",
+    "concat",
+    "quoteList",
+    "squoteList",
+    "base64encodeList",
+    "base64decodeList",
+    "toIntList",
+    "toFloatList",
+];
+
+const DICTS: &[&str] = &[
+    "getPath",
+    "setPath",
+    "hasKeyPath",
+    "hasKeyCI",
+    "getOr",
+    "mapKeysPrefix",
+    "mapValuesToString",
+    "valuesWhere",
+    "omitEmpty",
+    "mergeArrays",
+    "mergeDefaults",
+    "jsonpath",
+];
+
+const CONVERSION: &[&str] = &["int", "int64", "float64", "toBool"];
+
+const DEFAULTS: &[&str] = &["all", "any", "defaultIf", "required"];
+
+const ENCODING: &[&str] = &[
+    "b32dec",
+    "b32enc",
+    "b64dec",
+    "b64enc",
+    "base64encodeMime",
+    "base32crockfordEncode",
+    "base32crockfordDecode",
+    "csvParse",
+    "csvJoin",
+    "iniParse",
+    "dotenvParse",
+    "fromJsonArray",
+    "toJsonArray",
+    "jsonBase64",
+    "fromYaml",
+    "toYaml",
+];
+
+const REGEX: &[&str] = &[
+    "regexMatch",
+    "regexFind",
+    "regexFindAll",
+    "regexSplit",
+    "regexReplaceAll",
+    "regexReplaceAllLiteral",
+];
+
+const CRYPTO: &[&str] = &[
+    "adler32sum",
+    "crc32",
+    "md5sum",
+    "sha1sum",
+    "sha256sum",
+    "hmacSha256",
+    "signJwt",
+    "decodeJwt",
+];
+
+const IDS: &[&str] = &["uuidv3", "uuidv5", "ulid", "nanoid"];
+
+const TEMPLATES: &[&str] = &["tpl", "include"];
+
+/// Builds a map with a selection of the functions in `SPRIG`, by category
+/// and by name. Names unknown to `SPRIG`, e.g. crypto functions without the
+/// `crypto` feature, are skipped. Deprecated names of functions, e.g.
+/// `base64encode` for `b64enc`, are added and removed along with them.
+#[derive(Clone, Debug, Default)]
+pub struct Builder {
+    names: BTreeSet<String>,
+}
+
+impl Builder {
+    /// A builder without any functions.
+    pub fn new() -> Builder {
+        Builder::default()
+    }
+
+    fn with_all_of(self, names: &[&str]) -> Builder {
+        names.iter().fold(self, |b, name| b.with(name))
+    }
+
+    /// Add every function.
+    pub fn with_all(mut self) -> Builder {
+        self.names.extend(SPRIG.keys().cloned());
+        self.with_templates()
+    }
+
+    /// Add string manipulation and formatting functions, e.g. `upper`,
+    /// `indent` or `printf`.
+    pub fn with_strings(self) -> Builder {
+        self.with_all_of(STRINGS)
+    }
+
+    /// Add arithmetic functions, e.g. `add`, `round` or `until`.
+    pub fn with_math(self) -> Builder {
+        self.with_all_of(MATH)
+    }
+
+    /// Add list functions, e.g. `first` or `sample`.
+    pub fn with_lists(self) -> Builder {
+        self.with_all_of(LISTS)
+    }
+
+    /// Add map functions, e.g. `getPath` or `mergeDefaults`.
+    pub fn with_dicts(self) -> Builder {
+        self.with_all_of(DICTS)
+    }
+
+    /// Add type conversions, e.g. `int64` or `toBool`.
+    pub fn with_conversion(self) -> Builder {
+        self.with_all_of(CONVERSION)
+    }
+
+    /// Add functions dealing with empty values, e.g. `required`.
+    pub fn with_defaults(self) -> Builder {
+        self.with_all_of(DEFAULTS)
+    }
+
+    /// Add encoding and parsing functions for base 32 and 64, CSV, INI,
+    /// dotenv, JSON and YAML.
+    pub fn with_encoding(self) -> Builder {
+        self.with_all_of(ENCODING)
+    }
+
+    /// Add regular expression functions.
+    pub fn with_regex(self) -> Builder {
+        self.with_all_of(REGEX)
+    }
+
+    /// Add checksums, digests, HMACs and JSON Web Tokens.
+    pub fn with_crypto(self) -> Builder {
+        self.with_all_of(CRYPTO)
+    }
+
+    /// Add identifier generators, e.g. `uuidv5` or `ulid`.
+    pub fn with_ids(self) -> Builder {
+        self.with_all_of(IDS)
+    }
+
+    /// Add `tpl` and `include`, which render other templates. They are only
+    /// available through `render`.
+    pub fn with_templates(self) -> Builder {
+        self.with_all_of(TEMPLATES)
+    }
+
+    /// Add a single function by name, with its deprecated names.
+    pub fn with(mut self, name: &str) -> Builder {
+        let deprecated = aliases::ALIASES
+            .iter()
+            .map(|&(old, _)| old)
+            .filter(|old| aliases::canonical_name(old) == name);
+        self.names.extend(deprecated.map(|old| old.to_owned()));
+        self.names.insert(name.to_owned());
+        self
+    }
+
+    /// Remove a single function by name, with its deprecated names,
+    /// whichever category added it.
+    pub fn without(mut self, name: &str) -> Builder {
+        self.names
+            .retain(|n| n != name && aliases::canonical_name(n) != name);
+        self
+    }
+
+    fn selected(&self) -> HashMap<String, Func> {
+        self.names
+            .iter()
+            .filter_map(|n| SPRIG.get(n).map(|f| (n.clone(), *f)))
+            .collect()
+    }
+
+    /// The map of the selected functions, except `tpl` and `include`: outside
+    /// of `render`, `include` can't find the templates it renders and `tpl`
+    /// would give the templates it renders every function in `SPRIG`.
+    pub fn build(&self) -> HashMap<String, Func> {
+        let mut funcs = self.selected();
+        for name in TEMPLATES {
+            funcs.remove(*name);
+        }
+        funcs
+    }
+
+    /// Render a template with the selected functions. `include` can render
+    /// the templates `define`d in `text`, and templates rendered by `tpl` or
+    /// `include` only get the selected functions too.
+    pub fn render(&self, text: &str, context: Value) -> Result<String, String> {
+        let mut funcs = self.selected();
+        if self.names.contains("include") {
+            funcs.insert("include".to_owned(), tpl::include as Func);
+        }
+        tpl::render_template(text, context, &funcs)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_strings_only() {
+        let funcs = Builder::new().with_strings().build();
+        assert!(funcs.contains_key("upper"));
+        assert!(funcs.contains_key("indent"));
+        assert!(!funcs.contains_key("add"));
+        assert!(!funcs.contains_key("round"));
+        assert!(!funcs.contains_key("tpl"));
+    }
+
+    #[test]
+    fn test_without() {
+        let funcs = Builder::new()
+            .with_strings()
+            .with_math()
+            .without("randInt")
+            .without("now")
+            .build();
+        assert!(funcs.contains_key("upper"));
+        assert!(funcs.contains_key("add"));
+        assert!(!funcs.contains_key("randInt"));
+    }
+
+    #[test]
+    fn test_deprecated_names() {
+        let funcs = Builder::new().with_encoding().build();
+        assert!(funcs.contains_key("b64enc"));
+        assert!(funcs.contains_key("base64encode"));
+
+        let funcs = Builder::new().with("substr").build();
+        assert!(funcs.contains_key("substring"));
+
+        let funcs = Builder::new().with_all().without("b64enc").build();
+        assert!(!funcs.contains_key("b64enc"));
+        assert!(!funcs.contains_key("base64encode"));
+        assert!(funcs.contains_key("b64dec"));
+
+        let funcs = Builder::new().with_strings().without("substring").build();
+        assert!(funcs.contains_key("substr"));
+        assert!(!funcs.contains_key("substring"));
+    }
+
+    #[test]
+    fn test_with_all() {
+        let funcs = Builder::new().with_all().build();
+        assert_eq!(funcs.len(), SPRIG.len() - 1);
+        assert!(!funcs.contains_key("tpl"));
+        assert!(!funcs.contains_key("include"));
+        assert!(Builder::new().build().is_empty());
+    }
+
+    #[test]
+    fn test_render() {
+        let upper = Value::from("{{ upper . }}");
+        let builder = Builder::new().with_strings().with_templates();
+        let rendered = builder.render(r#"{{ tpl . "hi" }}"#, upper.clone());
+        assert_eq!(rendered, Ok("HI".to_owned()));
+        let add = Value::from("{{ add 1 2 }}");
+        assert!(builder.render(r#"{{ tpl . "hi" }}"#, add).is_err());
+        let builder = Builder::new().with_strings();
+        assert!(builder.render(r#"{{ tpl . "hi" }}"#, upper).is_err());
+    }
+
+    #[test]
+    fn test_every_function_has_a_category() {
+        let builder = Builder::new()
+            .with_strings()
+            .with_math()
+            .with_lists()
+            .with_dicts()
+            .with_conversion()
+            .with_defaults()
+            .with_encoding()
+            .with_regex()
+            .with_crypto()
+            .with_ids()
+            .with_templates();
+        for name in SPRIG.keys() {
+            assert!(builder.names.contains(name), "{} has no category", name);
+        }
+    }
+}
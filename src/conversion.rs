@@ -0,0 +1,172 @@
+use std::any::Any;
+use std::i64;
+use std::sync::Arc;
+
+use gtmpl_value::Value;
+
+use utils;
+
+/// Smallest `f64` that no longer fits into an `i64` (2^63).
+const I64_BOUND: f64 = 9_223_372_036_854_775_808.0;
+
+fn float_to_i64(f: f64) -> Result<i64, String> {
+    if f.is_nan() {
+        Err(String::from("NaN can not be converted to int64"))
+    } else if f >= I64_BOUND || f < -I64_BOUND {
+        Err(format!("{} overflows int64", f))
+    } else {
+        Ok(f.trunc() as i64)
+    }
+}
+
+pub fn value_to_i64(v: &Value) -> Result<i64, String> {
+    match *v {
+        Value::Number(ref n) => {
+            if let Some(i) = n.as_i64() {
+                Ok(i)
+            } else if let Some(u) = n.as_u64() {
+                Err(format!("{} overflows int64", u))
+            } else {
+                n.as_f64()
+                    .ok_or_else(|| "unable to convert number".to_owned())
+                    .and_then(float_to_i64)
+            }
+        }
+        Value::String(ref s) => {
+            let s = s.trim();
+            s.parse::<i64>().or_else(|_| {
+                s.parse::<f64>()
+                    .map_err(|_| format!("unable to parse {:?} as int64", s))
+                    .and_then(float_to_i64)
+            })
+        }
+        Value::Bool(b) => Ok(if b { 1 } else { 0 }),
+        Value::Nil | Value::NoValue => Ok(0),
+        _ => Err(format!("unable to convert {} to int64", v)),
+    }
+}
+
+pub fn value_to_f64(v: &Value) -> Result<f64, String> {
+    match *v {
+        Value::Number(ref n) => n.as_f64().ok_or_else(
+            || "unable to convert number".to_owned(),
+        ),
+        Value::String(ref s) => {
+            let s = s.trim();
+            s.parse::<f64>()
+                .map_err(|_| format!("unable to parse {:?} as float64", s))
+                .and_then(|f| if f.is_infinite() {
+                    Err(format!("{} overflows float64", s))
+                } else {
+                    Ok(f)
+                })
+        }
+        Value::Bool(b) => Ok(if b { 1.0 } else { 0.0 }),
+        Value::Nil | Value::NoValue => Ok(0.0),
+        _ => Err(format!("unable to convert {} to float64", v)),
+    }
+}
+
+fn value_to_bool(v: &Value) -> Result<bool, String> {
+    match *v {
+        Value::Bool(b) => Ok(b),
+        Value::Number(ref n) => Ok(n.as_f64().map(|f| f != 0.0).unwrap_or(true)),
+        Value::String(ref s) => {
+            match s.trim().to_lowercase().as_str() {
+                "true" | "yes" | "1" => Ok(true),
+                "false" | "no" | "0" => Ok(false),
+                _ => Err(format!("unable to parse {:?} as bool", s)),
+            }
+        }
+        _ => Err(format!("unable to convert {} to bool", v)),
+    }
+}
+
+/// Convert a number, numeric string or bool to an `int64`.
+///
+/// Unlike Go, which silently wraps, a value outside of the `int64` range
+/// results in an error. Floats are truncated towards zero.
+pub fn int64(args: &[Arc<Any>]) -> Result<Arc<Any>, String> {
+    utils::arg_count("int64", 1, args)?;
+    let arg0 = utils::downcast_arg("int64", args, 0)?;
+    value_to_i64(arg0).map(|i| Arc::new(Value::from(i)) as Arc<Any>)
+}
+
+/// Convert a number, numeric string or bool to a `float64`.
+///
+/// Strings exceeding the `float64` range result in an error instead of
+/// infinity.
+pub fn float64(args: &[Arc<Any>]) -> Result<Arc<Any>, String> {
+    utils::arg_count("float64", 1, args)?;
+    let arg0 = utils::downcast_arg("float64", args, 0)?;
+    value_to_f64(arg0).map(|f| Arc::new(Value::from(f)) as Arc<Any>)
+}
+
+/// Convert a bool, number or string to a bool. Numbers are true unless zero,
+/// the strings "true", "yes" and "1" are true and "false", "no" and "0" are
+/// false (ignoring case and surrounding whitespace). Anything else is an
+/// error.
+pub fn to_bool(args: &[Arc<Any>]) -> Result<Arc<Any>, String> {
+    utils::arg_count("toBool", 1, args)?;
+    let arg0 = utils::downcast_arg("toBool", args, 0)?;
+    value_to_bool(arg0).map(|b| Arc::new(Value::from(b)) as Arc<Any>)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::any::Any;
+    use std::sync::Arc;
+    use gtmpl_value::Value;
+
+    #[test]
+    fn test_int64() {
+        test_fn!(int64, vvarc!("42"), 42i64);
+        test_fn!(int64, vvarc!(" -7 "), -7i64);
+        test_fn!(int64, vvarc!(3.9), 3i64);
+        test_fn!(int64, vvarc!("3.9"), 3i64);
+        test_fn!(int64, vvarc!(true), 1i64);
+        test_fn!(int64, vvarc!("9223372036854775807"), i64::MAX);
+        test_fn_err!(int64, vvarc!("foo"));
+        assert_eq!(
+            int64(&vvarc!(1, 2)).unwrap_err(),
+            "int64: one argument required, got 2"
+        );
+    }
+
+    #[test]
+    fn test_int64_overflow() {
+        test_fn_err!(int64, vvarc!("9223372036854775808"));
+        test_fn_err!(int64, vvarc!("-1e19"));
+        test_fn_err!(int64, vvarc!(9223372036854775808u64));
+        test_fn_err!(int64, vvarc!(1e19));
+    }
+
+    #[test]
+    fn test_float64() {
+        test_fn!(float64, vvarc!("1.5"), 1.5f64);
+        test_fn!(float64, vvarc!(2), 2.0f64);
+        test_fn_err!(float64, vvarc!("1e400"));
+        test_fn_err!(float64, vvarc!("foo"));
+    }
+
+    #[test]
+    fn test_to_bool() {
+        test_fn!(to_bool, vvarc!(true), true);
+        test_fn!(to_bool, vvarc!(false), false);
+        test_fn!(to_bool, vvarc!(1), true);
+        test_fn!(to_bool, vvarc!(0), false);
+        test_fn!(to_bool, vvarc!(0.5), true);
+        test_fn!(to_bool, vvarc!("true"), true);
+        test_fn!(to_bool, vvarc!("False"), false);
+        test_fn!(to_bool, vvarc!("yes"), true);
+        test_fn!(to_bool, vvarc!(" NO "), false);
+        test_fn!(to_bool, vvarc!("1"), true);
+        test_fn!(to_bool, vvarc!("0"), false);
+        test_fn_err!(to_bool, vvarc!("maybe"));
+        assert_eq!(
+            to_bool(&vvarc!()).unwrap_err(),
+            "toBool: one argument required, got 0"
+        );
+    }
+}
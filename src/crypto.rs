@@ -0,0 +1,228 @@
+#[cfg(feature = "crypto")]
+use std::any::Any;
+#[cfg(feature = "crypto")]
+use std::sync::Arc;
+#[cfg(feature = "crypto")]
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crc::crc32::checksum_ieee;
+#[cfg(feature = "crypto")]
+use hmac::{Hmac, Mac};
+#[cfg(feature = "crypto")]
+use jsonwebtoken::{self, Header, Validation};
+#[cfg(feature = "crypto")]
+use md5::{Digest, Md5};
+#[cfg(feature = "crypto")]
+use serde_json;
+#[cfg(feature = "crypto")]
+use sha1::Sha1;
+#[cfg(feature = "crypto")]
+use sha2::Sha256;
+
+#[cfg(feature = "crypto")]
+use gtmpl_value::{from_value, Value};
+
+#[cfg(feature = "crypto")]
+use serde_value;
+#[cfg(feature = "crypto")]
+use utils;
+
+gtmpl_fn!(
+#[doc = r#"CRC-32 (IEEE) checksum of a string, as a decimal string."#]
+fn crc32(s: String) -> Result<String, String> {
+    Ok(checksum_ieee(s.as_bytes()).to_string())
+}
+);
+
+gtmpl_fn!(
+#[doc = r#"Adler-32 checksum of a string, as a decimal string."#]
+fn adler32sum(s: String) -> Result<String, String> {
+    const MOD_ADLER: u32 = 65_521;
+    let (mut a, mut b) = (1u32, 0u32);
+    for byte in s.bytes() {
+        a = (a + u32::from(byte)) % MOD_ADLER;
+        b = (b + a) % MOD_ADLER;
+    }
+    Ok(((b << 16) | a).to_string())
+}
+);
+
+gtmpl_fn!(
+#[doc = r#"MD5 digest of a string, in lower case hex. Only use it where MD5 is
+required, e.g. for ETags, it is not secure."#]
+#[cfg(feature = "crypto")]
+fn md5sum(s: String) -> Result<String, String> {
+    Ok(format!("{:x}", Md5::digest(s.as_bytes())))
+}
+);
+
+gtmpl_fn!(
+#[doc = r#"SHA-1 digest of a string, in lower case hex."#]
+#[cfg(feature = "crypto")]
+fn sha1sum(s: String) -> Result<String, String> {
+    Ok(format!("{:x}", Sha1::digest(s.as_bytes())))
+}
+);
+
+gtmpl_fn!(
+#[doc = r#"SHA-256 digest of a string, in lower case hex."#]
+#[cfg(feature = "crypto")]
+fn sha256sum(s: String) -> Result<String, String> {
+    Ok(format!("{:x}", Sha256::digest(s.as_bytes())))
+}
+);
+
+gtmpl_fn!(
+#[doc = r#"HMAC-SHA256 of a message, as `hmacSha256 KEY MESSAGE`, in lower case hex."#]
+#[cfg(feature = "crypto")]
+fn hmac_sha256(key: String, message: String) -> Result<String, String> {
+    let mut mac = Hmac::<Sha256>::new(key.as_bytes()).map_err(
+        |e| format!("invalid key: {:?}", e),
+    )?;
+    mac.input(message.as_bytes());
+    Ok(format!("{:x}", mac.result().code()))
+}
+);
+
+/// Sign a map of claims as an HS256 JSON Web Token, as
+/// `signJwt CLAIMS SECRET`.
+#[cfg(feature = "crypto")]
+pub fn sign_jwt(args: &[Arc<Any>]) -> Result<Arc<Any>, String> {
+    utils::arg_count("signJwt", 2, args)?;
+    let claims = utils::downcast_arg("signJwt", args, 0)?;
+    match *claims {
+        Value::Map(_) | Value::Object(_) => {}
+        ref v => return Err(utils::type_error("signJwt", 1, "map", v)),
+    }
+    let arg1 = utils::downcast_arg("signJwt", args, 1)?;
+    let secret: String = from_value(arg1).ok_or_else(|| {
+        utils::type_error("signJwt", 2, "string", arg1)
+    })?;
+    jsonwebtoken::encode(
+        &Header::default(),
+        &serde_value::to_serde(claims),
+        secret.as_bytes(),
+    ).map(|t| Arc::new(Value::from(t)) as Arc<Any>)
+        .map_err(|e| format!("signJwt: {}", e))
+}
+
+/// Verify an HS256 JSON Web Token and return its claims, as
+/// `decodeJwt TOKEN SECRET`. A bad signature or an `exp` claim in the past
+/// is an error.
+#[cfg(feature = "crypto")]
+pub fn decode_jwt(args: &[Arc<Any>]) -> Result<Arc<Any>, String> {
+    utils::arg_count("decodeJwt", 2, args)?;
+    let mut strings = vec![];
+    for i in 0..2 {
+        let arg = utils::downcast_arg("decodeJwt", args, i)?;
+        strings.push(from_value::<String>(arg).ok_or_else(|| {
+            utils::type_error("decodeJwt", i + 1, "string", arg)
+        })?);
+    }
+    // A missing `exp` is fine, so it's checked here instead of by
+    // jsonwebtoken, which would reject it.
+    let validation = Validation {
+        validate_exp: false,
+        ..Validation::default()
+    };
+    let data = jsonwebtoken::decode::<serde_json::Value>(
+        &strings[0],
+        strings[1].as_bytes(),
+        &validation,
+    ).map_err(|e| format!("decodeJwt: {}", e))?;
+    if let Some(exp) = data.claims.get("exp").and_then(|e| e.as_u64()) {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        if exp < now {
+            return Err(String::from("decodeJwt: token expired"));
+        }
+    }
+    Ok(Arc::new(serde_value::from_serde(&data.claims)))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::any::Any;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+    use gtmpl_value::Value;
+
+    #[test]
+    fn test_crc32() {
+        test_fn!(crc32, vvarc!("123456789"), "3421780262");
+        test_fn!(crc32, vvarc!(""), "0");
+    }
+
+    #[test]
+    fn test_adler32sum() {
+        test_fn!(adler32sum, vvarc!("Hello World!"), "474547262");
+        test_fn!(adler32sum, vvarc!("Wikipedia"), "300286872");
+        test_fn!(adler32sum, vvarc!(""), "1");
+    }
+
+    #[test]
+    #[cfg(feature = "crypto")]
+    fn test_md5sum() {
+        test_fn!(md5sum, vvarc!(""), "d41d8cd98f00b204e9800998ecf8427e");
+        test_fn!(md5sum, vvarc!("abc"), "900150983cd24fb0d6963f7d28e17f72");
+    }
+
+    #[test]
+    #[cfg(feature = "crypto")]
+    fn test_sha1sum() {
+        test_fn!(sha1sum, vvarc!(""), "da39a3ee5e6b4b0d3255bfef95601890afd80709");
+        test_fn!(sha1sum, vvarc!("Hello world!"), "d3486ae9136e7856bc42212385ea797094475802");
+    }
+
+    #[test]
+    #[cfg(feature = "crypto")]
+    fn test_sha256sum() {
+        test_fn!(
+            sha256sum,
+            vvarc!(""),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+        test_fn!(
+            sha256sum,
+            vvarc!("Hello world!"),
+            "c0535e4be2b79ffd93291305436bf889314e4a3faec05ecffcbb7df31ad9e51a"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "crypto")]
+    fn test_hmac_sha256() {
+        // RFC 4231, test case 2
+        test_fn!(
+            hmac_sha256,
+            vvarc!("Jefe", "what do ya want for nothing?"),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "crypto")]
+    fn test_jwt_round_trip() {
+        let mut claims = HashMap::new();
+        claims.insert("sub".to_owned(), Value::from("alice"));
+        claims.insert("admin".to_owned(), Value::from(true));
+        let claims = Value::Map(claims);
+        let token = sign_jwt(&vvarc!(claims.clone(), "secret")).unwrap();
+        let token = token.downcast_ref::<Value>().unwrap().clone();
+        test_fn!(decode_jwt, vvarc!(token.clone(), "secret"), claims);
+        test_fn_err!(decode_jwt, vvarc!(token, "wrong"));
+    }
+
+    #[test]
+    #[cfg(feature = "crypto")]
+    fn test_jwt_expired() {
+        let mut claims = HashMap::new();
+        claims.insert("exp".to_owned(), Value::from(1));
+        let token = sign_jwt(&vvarc!(Value::Map(claims), "secret")).unwrap();
+        let token = token.downcast_ref::<Value>().unwrap().clone();
+        test_fn_err!(decode_jwt, vvarc!(token, "secret"));
+    }
+}
@@ -0,0 +1,88 @@
+use std::any::Any;
+use std::sync::Arc;
+
+use csv_crate::{ReaderBuilder, WriterBuilder};
+
+use gtmpl_value::Value;
+
+use utils;
+
+gtmpl_fn!(
+#[doc = r#"Parse CSV into a list of rows, each a list of fields. There is no header
+row and rows may differ in length."#]
+fn csv_parse(s: String) -> Result<Vec<Vec<String>>, String> {
+    ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(s.as_bytes())
+        .records()
+        .map(|r| {
+            r.map(|r| r.iter().map(|f| f.to_owned()).collect())
+                .map_err(|e| format!("unable to parse csv: {}", e))
+        })
+        .collect()
+}
+);
+
+/// Serialize a list of rows, each a list of fields, to CSV, as `csvJoin ROWS`.
+/// Fields are quoted where necessary.
+pub fn csv_join(args: &[Arc<Any>]) -> Result<Arc<Any>, String> {
+    utils::arg_count("csvJoin", 1, args)?;
+    let arg0 = utils::downcast_arg("csvJoin", args, 0)?;
+    let rows = match *arg0 {
+        Value::Array(ref rows) => rows,
+        ref v => return Err(utils::type_error("csvJoin", 1, "list", v)),
+    };
+    let mut w = WriterBuilder::new().flexible(true).from_writer(vec![]);
+    for (i, row) in rows.iter().enumerate() {
+        if let Value::Array(ref fields) = *row {
+            w.write_record(fields.iter().map(|f| f.to_string()))
+                .map_err(|e| format!("unable to write csv: {}", e))?;
+        } else {
+            return Err(format!("csvJoin: row {} must be a list of fields", i));
+        }
+    }
+    let bytes = w.into_inner().map_err(
+        |e| format!("unable to write csv: {}", e),
+    )?;
+    String::from_utf8(bytes)
+        .map(|s| Arc::new(Value::from(s)) as Arc<Any>)
+        .map_err(|e| format!("unable to write csv: {}", e))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::any::Any;
+    use std::sync::Arc;
+    use gtmpl_value::Value;
+
+    const CSV: &str = "a,\"b,c\",\"say \"\"hi\"\"\"\n1,2\n";
+
+    fn rows() -> Vec<Vec<&'static str>> {
+        vec![vec!["a", "b,c", "say \"hi\""], vec!["1", "2"]]
+    }
+
+    #[test]
+    fn test_csv_parse() {
+        test_fn!(csv_parse, vvarc!(CSV), rows());
+        test_fn!(csv_parse, vvarc!(""), Vec::<Vec<String>>::new());
+    }
+
+    #[test]
+    fn test_csv_join() {
+        test_fn!(csv_join, vvarc!(rows()), CSV);
+        test_fn_err!(csv_join, vvarc!(vec!["a"]));
+        assert_eq!(
+            csv_join(&vvarc!("a,b")).unwrap_err(),
+            "csvJoin: argument 1 expected list, got string"
+        );
+    }
+
+    #[test]
+    fn test_csv_round_trip() {
+        let parsed = csv_parse(&vvarc!(CSV)).unwrap();
+        let v: Vec<Arc<Any>> = vec![parsed];
+        test_fn!(csv_join, v, CSV);
+    }
+}
@@ -0,0 +1,107 @@
+use std::any::Any;
+use std::sync::Arc;
+
+use gtmpl_value::{from_value, Value};
+
+use utils;
+
+/// All arguments of the function named `name`.
+fn values<'a>(name: &str, args: &'a [Arc<Any>]) -> Result<Vec<&'a Value>, String> {
+    (0..args.len())
+        .map(|i| utils::downcast_arg(name, args, i))
+        .collect()
+}
+
+/// Whether none of the arguments is empty, as `all A B ...`. Nil, `false`,
+/// `0` and empty strings, lists and maps are empty. `all` without arguments
+/// is true.
+pub fn all(args: &[Arc<Any>]) -> Result<Arc<Any>, String> {
+    let values = values("all", args)?;
+    Ok(Arc::new(Value::from(values.iter().all(|v| !utils::is_empty(v)))))
+}
+
+/// Whether any of the arguments is not empty, as `any A B ...`. `any`
+/// without arguments is false.
+pub fn any(args: &[Arc<Any>]) -> Result<Arc<Any>, String> {
+    let values = values("any", args)?;
+    Ok(Arc::new(Value::from(values.iter().any(|v| !utils::is_empty(v)))))
+}
+
+/// `default` if `condition` is not empty, otherwise `value`, as
+/// `defaultIf CONDITION DEFAULT VALUE`.
+///
+/// Like every function (and Sprig's `ternary`), all arguments are evaluated
+/// by the template before the call, so this can't be used to skip evaluating
+/// an expensive or failing branch.
+pub fn default_if(args: &[Arc<Any>]) -> Result<Arc<Any>, String> {
+    utils::arg_count("defaultIf", 3, args)?;
+    let cond = utils::downcast_arg("defaultIf", args, 0)?;
+    Ok(if utils::is_empty(cond) {
+        Arc::clone(&args[2])
+    } else {
+        Arc::clone(&args[1])
+    })
+}
+
+/// `value` unchanged, or an error with `message` if it is empty, as
+/// `required MESSAGE VALUE`, like Helm's `required`.
+pub fn required(args: &[Arc<Any>]) -> Result<Arc<Any>, String> {
+    utils::arg_count("required", 2, args)?;
+    let arg0 = utils::downcast_arg("required", args, 0)?;
+    let message: String = from_value(arg0).ok_or_else(|| {
+        utils::type_error("required", 1, "string", arg0)
+    })?;
+    if utils::is_empty(utils::downcast_arg("required", args, 1)?) {
+        return Err(message);
+    }
+    Ok(Arc::clone(&args[1]))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::any::Any;
+    use std::sync::Arc;
+    use gtmpl_value::Value;
+
+    #[test]
+    fn test_all() {
+        test_fn!(all, vvarc!("a", "b"), true);
+        test_fn!(all, vvarc!("a", 0), false);
+        test_fn!(all, vvarc!(1, true, vec!["x"]), true);
+        test_fn!(all, vvarc!("a", Vec::<String>::new()), false);
+        test_fn!(all, vvarc!(), true);
+    }
+
+    #[test]
+    fn test_any() {
+        test_fn!(any, vvarc!("", 0, "x"), true);
+        test_fn!(any, vvarc!("", 0, false, 0.0), false);
+        test_fn!(any, vvarc!(), false);
+    }
+
+    #[test]
+    fn test_default_if() {
+        test_fn!(default_if, vvarc!(true, "fallback", "value"), "fallback");
+        test_fn!(default_if, vvarc!("x", 1, 2), 1i64);
+        test_fn!(default_if, vvarc!(false, "fallback", "value"), "value");
+        test_fn!(default_if, vvarc!("", "fallback", "value"), "value");
+        test_fn_err!(default_if, vvarc!(true, "fallback"));
+        assert_eq!(
+            default_if(&vvarc!(true, "fallback")).unwrap_err(),
+            "defaultIf: three arguments required, got 2"
+        );
+    }
+
+    #[test]
+    fn test_required() {
+        test_fn!(required, vvarc!("name is required", "foo"), "foo");
+        test_fn!(required, vvarc!("replicas is required", 3), 3i64);
+        test_fn_err!(required, vvarc!("name is required", ""));
+        test_fn_err!(required, vvarc!("name is required", Value::Nil));
+        assert_eq!(
+            required(&vvarc!("name is required", "")).err(),
+            Some("name is required".to_owned())
+        );
+    }
+}
@@ -0,0 +1,455 @@
+use std::any::Any;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use gtmpl_value::{from_value, Value};
+
+use utils;
+
+/// Argument `i` of the function named `name`, which must be a map.
+fn map_arg<'a>(
+    name: &str,
+    args: &'a [Arc<Any>],
+    i: usize,
+) -> Result<&'a HashMap<String, Value>, String> {
+    match *utils::downcast_arg(name, args, i)? {
+        Value::Map(ref m) |
+        Value::Object(ref m) => Ok(m),
+        ref v => Err(utils::type_error(name, i + 1, "map", v)),
+    }
+}
+
+/// Argument `i` of the function named `name`, which must be a string.
+fn str_arg(name: &str, args: &[Arc<Any>], i: usize) -> Result<String, String> {
+    let arg = utils::downcast_arg(name, args, i)?;
+    from_value(arg).ok_or_else(|| utils::type_error(name, i + 1, "string", arg))
+}
+
+/// Argument `i` of the function named `name`, a non-empty dotted path.
+fn path_arg(name: &str, args: &[Arc<Any>], i: usize) -> Result<Vec<String>, String> {
+    let path = str_arg(name, args, i)?;
+    if path.is_empty() {
+        return Err(format!("{}: path must not be empty", name));
+    }
+    Ok(path.split('.').map(|k| k.to_owned()).collect())
+}
+
+fn lookup<'a>(v: &'a Value, path: &[String]) -> Option<&'a Value> {
+    path.iter().fold(Some(v), |v, key| match v {
+        Some(&Value::Map(ref m)) |
+        Some(&Value::Object(ref m)) => m.get(key),
+        _ => None,
+    })
+}
+
+fn insert(m: &mut HashMap<String, Value>, path: &[String], value: Value) -> Result<(), String> {
+    let (key, rest) = path.split_first().expect("path must not be empty");
+    if rest.is_empty() {
+        m.insert(key.clone(), value);
+        return Ok(());
+    }
+    let child = m.entry(key.clone()).or_insert_with(
+        || Value::Map(HashMap::new()),
+    );
+    match *child {
+        Value::Map(ref mut m) |
+        Value::Object(ref mut m) => insert(m, rest, value),
+        _ => Err(format!("{} is not a map", key)),
+    }
+}
+
+/// Look up a nested value by a dotted path, as `getPath "a.b.c" DICT`. A
+/// missing path yields nil.
+pub fn get_path(args: &[Arc<Any>]) -> Result<Arc<Any>, String> {
+    utils::arg_count("getPath", 2, args)?;
+    let path = path_arg("getPath", args, 0)?;
+    let dict = utils::downcast_arg("getPath", args, 1)?;
+    Ok(Arc::new(lookup(dict, &path).cloned().unwrap_or(Value::Nil)))
+}
+
+/// Set a nested value by a dotted path, as `setPath "a.b.c" VALUE DICT`,
+/// creating missing maps along the way. Yields the updated dict. Setting a
+/// path through a value which isn't a map is an error.
+pub fn set_path(args: &[Arc<Any>]) -> Result<Arc<Any>, String> {
+    utils::arg_count("setPath", 3, args)?;
+    let path = path_arg("setPath", args, 0)?;
+    let value = utils::downcast_arg("setPath", args, 1)?.clone();
+    let mut dict = utils::downcast_arg("setPath", args, 2)?.clone();
+    match dict {
+        Value::Map(ref mut m) |
+        Value::Object(ref mut m) => insert(m, &path, value)?,
+        ref v => return Err(utils::type_error("setPath", 3, "map", v)),
+    }
+    Ok(Arc::new(dict))
+}
+
+/// Whether a nested value exists at a dotted path, as
+/// `hasKeyPath "a.b.c" DICT`.
+pub fn has_key_path(args: &[Arc<Any>]) -> Result<Arc<Any>, String> {
+    utils::arg_count("hasKeyPath", 2, args)?;
+    let path = path_arg("hasKeyPath", args, 0)?;
+    let dict = utils::downcast_arg("hasKeyPath", args, 1)?;
+    Ok(Arc::new(Value::from(lookup(dict, &path).is_some())))
+}
+
+/// The value of a key in a map, or `default` if the key is missing, as
+/// `getOr DICT KEY DEFAULT`. A key which is present is returned even if its
+/// value is empty.
+pub fn get_or(args: &[Arc<Any>]) -> Result<Arc<Any>, String> {
+    utils::arg_count("getOr", 3, args)?;
+    let dict = map_arg("getOr", args, 0)?;
+    let key = str_arg("getOr", args, 1)?;
+    Ok(match dict.get(&key) {
+        Some(v) => Arc::new(v.clone()),
+        None => Arc::clone(&args[2]),
+    })
+}
+
+/// Whether a map has a key, ignoring case, as `hasKeyCI DICT KEY`, e.g. for
+/// maps of HTTP headers.
+pub fn has_key_ci(args: &[Arc<Any>]) -> Result<Arc<Any>, String> {
+    utils::arg_count("hasKeyCI", 2, args)?;
+    let dict = map_arg("hasKeyCI", args, 0)?;
+    let key = str_arg("hasKeyCI", args, 1)?;
+    let key = key.to_lowercase();
+    Ok(Arc::new(
+        Value::from(dict.keys().any(|k| k.to_lowercase() == key)),
+    ))
+}
+
+/// Prefix every key of a map, as `mapKeysPrefix PREFIX DICT`.
+pub fn map_keys_prefix(args: &[Arc<Any>]) -> Result<Arc<Any>, String> {
+    utils::arg_count("mapKeysPrefix", 2, args)?;
+    let prefix = str_arg("mapKeysPrefix", args, 0)?;
+    let dict = map_arg("mapKeysPrefix", args, 1)?;
+    Ok(Arc::new(Value::Map(
+        dict.iter()
+            .map(|(k, v)| (format!("{}{}", prefix, k), v.clone()))
+            .collect(),
+    )))
+}
+
+/// The values of all keys starting with a prefix, sorted by key, as
+/// `valuesWhere DICT PREFIX`.
+pub fn values_where(args: &[Arc<Any>]) -> Result<Arc<Any>, String> {
+    utils::arg_count("valuesWhere", 2, args)?;
+    let dict = map_arg("valuesWhere", args, 0)?;
+    let prefix = str_arg("valuesWhere", args, 1)?;
+    Ok(Arc::new(Value::Array(
+        utils::sorted_entries(dict)
+            .into_iter()
+            .filter(|&(k, _)| k.starts_with(&prefix))
+            .map(|(_, v)| v.clone())
+            .collect(),
+    )))
+}
+
+/// Convert every value of a map to a string, as `mapValuesToString DICT`.
+pub fn map_values_to_string(args: &[Arc<Any>]) -> Result<Arc<Any>, String> {
+    utils::arg_count("mapValuesToString", 1, args)?;
+    let dict = map_arg("mapValuesToString", args, 0)?;
+    Ok(Arc::new(Value::Map(
+        dict.iter()
+            .map(|(k, v)| (k.clone(), Value::from(v.to_string())))
+            .collect(),
+    )))
+}
+
+/// Remove all entries with empty values (nil, `false`, `0`, or an empty
+/// string, list or map) from a map, as `omitEmpty DICT`.
+pub fn omit_empty(args: &[Arc<Any>]) -> Result<Arc<Any>, String> {
+    utils::arg_count("omitEmpty", 1, args)?;
+    let dict = map_arg("omitEmpty", args, 0)?;
+    Ok(Arc::new(Value::Map(
+        dict.iter()
+            .filter(|&(_, v)| !utils::is_empty(v))
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect(),
+    )))
+}
+
+fn merge_arrays_into(dst: &mut HashMap<String, Value>, src: &HashMap<String, Value>) {
+    for (k, v) in src {
+        if !dst.contains_key(k) {
+            dst.insert(k.clone(), v.clone());
+            continue;
+        }
+        match (dst.get_mut(k).unwrap(), v) {
+            (&mut Value::Map(ref mut d), &Value::Map(ref s)) |
+            (&mut Value::Map(ref mut d), &Value::Object(ref s)) |
+            (&mut Value::Object(ref mut d), &Value::Map(ref s)) |
+            (&mut Value::Object(ref mut d), &Value::Object(ref s)) => {
+                merge_arrays_into(d, s)
+            }
+            (&mut Value::Array(ref mut d), &Value::Array(ref s)) => {
+                d.extend(s.iter().cloned())
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Deep merge maps into the first one, as `mergeArrays DST SRC...`.
+///
+/// Like Sprig's `merge`, values already present in `DST` (or an earlier
+/// source) win and nested maps are merged recursively. Unlike `merge`, lists
+/// found under the same key are concatenated instead of the first one
+/// replacing the others.
+pub fn merge_arrays(args: &[Arc<Any>]) -> Result<Arc<Any>, String> {
+    if args.is_empty() {
+        return Err(String::from("mergeArrays: at least one argument required"));
+    }
+    let mut dst = map_arg("mergeArrays", args, 0)?.clone();
+    for i in 1..args.len() {
+        merge_arrays_into(&mut dst, map_arg("mergeArrays", args, i)?);
+    }
+    Ok(Arc::new(Value::Map(dst)))
+}
+
+fn merge_defaults_into(dst: &mut HashMap<String, Value>, defaults: &HashMap<String, Value>) {
+    for (k, v) in defaults {
+        if !dst.contains_key(k) {
+            dst.insert(k.clone(), v.clone());
+            continue;
+        }
+        match (dst.get_mut(k).unwrap(), v) {
+            (&mut Value::Map(ref mut d), &Value::Map(ref s)) |
+            (&mut Value::Map(ref mut d), &Value::Object(ref s)) |
+            (&mut Value::Object(ref mut d), &Value::Map(ref s)) |
+            (&mut Value::Object(ref mut d), &Value::Object(ref s)) => {
+                merge_defaults_into(d, s)
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Fill the keys missing from a map with those of a map of defaults, as
+/// `mergeDefaults DICT DEFAULTS`. Nested maps are filled in recursively,
+/// any other value in `DICT` wins over its default.
+pub fn merge_defaults(args: &[Arc<Any>]) -> Result<Arc<Any>, String> {
+    utils::arg_count("mergeDefaults", 2, args)?;
+    let mut dict = map_arg("mergeDefaults", args, 0)?.clone();
+    merge_defaults_into(&mut dict, map_arg("mergeDefaults", args, 1)?);
+    Ok(Arc::new(Value::Map(dict)))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::any::Any;
+    use std::sync::Arc;
+    use gtmpl_value::Value;
+
+    fn nested(v: Value) -> Value {
+        let mut c = HashMap::new();
+        c.insert("c".to_owned(), v);
+        let mut b = HashMap::new();
+        b.insert("b".to_owned(), Value::Map(c));
+        let mut a = HashMap::new();
+        a.insert("a".to_owned(), Value::Map(b));
+        Value::Map(a)
+    }
+
+    #[test]
+    fn test_get_path() {
+        test_fn!(get_path, vvarc!("a.b.c", nested(Value::from(42))), 42i64);
+        test_fn!(get_path, vvarc!("a.x.c", nested(Value::from(42))), Value::Nil);
+        test_fn!(get_path, vvarc!("a.b.c.d", nested(Value::from(42))), Value::Nil);
+        test_fn_err!(get_path, vvarc!("", nested(Value::from(42))));
+    }
+
+    #[test]
+    fn test_set_path() {
+        let empty = Value::Map(HashMap::new());
+        test_fn!(set_path, vvarc!("a.b.c", 42, empty), nested(Value::from(42)));
+        test_fn!(
+            set_path,
+            vvarc!("a.b.c", 7, nested(Value::from(42))),
+            nested(Value::from(7))
+        );
+        test_fn_err!(set_path, vvarc!("a.b.c.d", 7, nested(Value::from(42))));
+        assert_eq!(
+            set_path(&vvarc!("a", 7, "x")).unwrap_err(),
+            "setPath: argument 3 expected map, got string"
+        );
+        assert_eq!(
+            get_path(&vvarc!("a")).unwrap_err(),
+            "getPath: two arguments required, got 1"
+        );
+    }
+
+    #[test]
+    fn test_has_key_path() {
+        test_fn!(has_key_path, vvarc!("a.b.c", nested(Value::Nil)), true);
+        test_fn!(has_key_path, vvarc!("a.b", nested(Value::Nil)), true);
+        test_fn!(has_key_path, vvarc!("a.b.x", nested(Value::Nil)), false);
+        test_fn!(has_key_path, vvarc!("a.b.c.d", nested(Value::Nil)), false);
+        assert_eq!(
+            has_key_path(&vvarc!("a")).unwrap_err(),
+            "hasKeyPath: two arguments required, got 1"
+        );
+    }
+
+    #[test]
+    fn test_get_or() {
+        let mut m = HashMap::new();
+        m.insert("port".to_owned(), Value::from(80));
+        m.insert("host".to_owned(), Value::from(""));
+        test_fn!(get_or, vvarc!(Value::Map(m.clone()), "port", 8080), 80i64);
+        test_fn!(get_or, vvarc!(Value::Map(m.clone()), "host", "localhost"), "");
+        test_fn!(get_or, vvarc!(Value::Map(m.clone()), "user", "nobody"), "nobody");
+        test_fn_err!(get_or, vvarc!(Value::Map(m), "port"));
+    }
+
+    #[test]
+    fn test_has_key_ci() {
+        let mut m = HashMap::new();
+        m.insert("Content-Type".to_owned(), Value::from(1));
+        test_fn!(has_key_ci, vvarc!(Value::Map(m.clone()), "content-type"), true);
+        test_fn!(has_key_ci, vvarc!(Value::Map(m.clone()), "CONTENT-TYPE"), true);
+        test_fn!(has_key_ci, vvarc!(Value::Map(m.clone()), "Content-Length"), false);
+        test_fn_err!(has_key_ci, vvarc!("content-type", Value::Map(m)));
+    }
+
+    #[test]
+    fn test_map_keys_prefix() {
+        let mut m = HashMap::new();
+        m.insert("a".to_owned(), Value::from(1));
+        m.insert("b".to_owned(), Value::from("x"));
+        let mut expected = HashMap::new();
+        expected.insert("pre_a".to_owned(), Value::from(1));
+        expected.insert("pre_b".to_owned(), Value::from("x"));
+        test_fn!(map_keys_prefix, vvarc!("pre_", Value::Map(m)), Value::Map(expected));
+        test_fn_err!(map_keys_prefix, vvarc!("pre_", "a"));
+        assert_eq!(
+            map_keys_prefix(&vvarc!(1, Value::Map(HashMap::new()))).unwrap_err(),
+            "mapKeysPrefix: argument 1 expected string, got number"
+        );
+    }
+
+    #[test]
+    fn test_values_where() {
+        let mut m = HashMap::new();
+        m.insert("db_user".to_owned(), Value::from("admin"));
+        m.insert("db_host".to_owned(), Value::from("localhost"));
+        m.insert("db_port".to_owned(), Value::from(5432));
+        m.insert("cache_host".to_owned(), Value::from("redis"));
+        m.insert("db".to_owned(), Value::from("main"));
+        test_fn!(
+            values_where,
+            vvarc!(Value::Map(m.clone()), "db_"),
+            Value::Array(vec![
+                Value::from("localhost"),
+                Value::from(5432),
+                Value::from("admin"),
+            ])
+        );
+        test_fn!(values_where, vvarc!(Value::Map(m), "web_"), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_map_values_to_string() {
+        let mut m = HashMap::new();
+        m.insert("a".to_owned(), Value::from(1));
+        m.insert("b".to_owned(), Value::from(true));
+        m.insert("c".to_owned(), Value::from("x"));
+        let mut expected = HashMap::new();
+        expected.insert("a".to_owned(), Value::from("1"));
+        expected.insert("b".to_owned(), Value::from("true"));
+        expected.insert("c".to_owned(), Value::from("x"));
+        test_fn!(map_values_to_string, vvarc!(Value::Map(m)), Value::Map(expected));
+        assert_eq!(
+            map_values_to_string(&vvarc!()).unwrap_err(),
+            "mapValuesToString: one argument required, got 0"
+        );
+    }
+
+    #[test]
+    fn test_omit_empty() {
+        let mut m = HashMap::new();
+        m.insert("name".to_owned(), Value::from("foo"));
+        m.insert("empty".to_owned(), Value::from(""));
+        m.insert("zero".to_owned(), Value::from(0));
+        m.insert("nil".to_owned(), Value::Nil);
+        m.insert("on".to_owned(), Value::from(true));
+        let mut expected = HashMap::new();
+        expected.insert("name".to_owned(), Value::from("foo"));
+        expected.insert("on".to_owned(), Value::from(true));
+        test_fn!(omit_empty, vvarc!(Value::Map(m)), Value::Map(expected));
+        assert_eq!(
+            omit_empty(&vvarc!()).unwrap_err(),
+            "omitEmpty: one argument required, got 0"
+        );
+    }
+
+    #[test]
+    fn test_merge_arrays() {
+        let mut dst = HashMap::new();
+        dst.insert("list".to_owned(), Value::from(vec![1, 2]));
+        dst.insert("name".to_owned(), Value::from("dst"));
+        let mut src = HashMap::new();
+        src.insert("list".to_owned(), Value::from(vec![3]));
+        src.insert("name".to_owned(), Value::from("src"));
+        src.insert("extra".to_owned(), Value::from(true));
+        let mut expected = HashMap::new();
+        expected.insert("list".to_owned(), Value::from(vec![1, 2, 3]));
+        expected.insert("name".to_owned(), Value::from("dst"));
+        expected.insert("extra".to_owned(), Value::from(true));
+        test_fn!(
+            merge_arrays,
+            vvarc!(Value::Map(dst), Value::Map(src)),
+            Value::Map(expected)
+        );
+    }
+
+    #[test]
+    fn test_merge_arrays_nested() {
+        let dst = nested(Value::from(vec!["a"]));
+        let src = nested(Value::from(vec!["b"]));
+        test_fn!(merge_arrays, vvarc!(dst, src), nested(Value::from(vec!["a", "b"])));
+        test_fn_err!(merge_arrays, vvarc!(nested(Value::Nil), "a"));
+        assert_eq!(
+            merge_arrays(&vvarc!()).unwrap_err(),
+            "mergeArrays: at least one argument required"
+        );
+    }
+
+    #[test]
+    fn test_merge_defaults() {
+        let mut image = HashMap::new();
+        image.insert("tag".to_owned(), Value::from("1.2"));
+        let mut dict = HashMap::new();
+        dict.insert("image".to_owned(), Value::Map(image));
+        dict.insert("replicas".to_owned(), Value::from(3));
+        dict.insert("ports".to_owned(), Value::from(vec![8080]));
+
+        let mut default_image = HashMap::new();
+        default_image.insert("repository".to_owned(), Value::from("nginx"));
+        default_image.insert("tag".to_owned(), Value::from("latest"));
+        let mut defaults = HashMap::new();
+        defaults.insert("image".to_owned(), Value::Map(default_image));
+        defaults.insert("replicas".to_owned(), Value::from(1));
+        defaults.insert("ports".to_owned(), Value::from(vec![80]));
+        defaults.insert("debug".to_owned(), Value::from(false));
+
+        let mut expected_image = HashMap::new();
+        expected_image.insert("repository".to_owned(), Value::from("nginx"));
+        expected_image.insert("tag".to_owned(), Value::from("1.2"));
+        let mut expected = HashMap::new();
+        expected.insert("image".to_owned(), Value::Map(expected_image));
+        expected.insert("replicas".to_owned(), Value::from(3));
+        expected.insert("ports".to_owned(), Value::from(vec![8080]));
+        expected.insert("debug".to_owned(), Value::from(false));
+        test_fn!(
+            merge_defaults,
+            vvarc!(Value::Map(dict), Value::Map(defaults)),
+            Value::Map(expected)
+        );
+        test_fn_err!(merge_defaults, vvarc!("a", Value::Map(HashMap::new())));
+        assert_eq!(
+            merge_defaults(&vvarc!(Value::Map(HashMap::new()))).err(),
+            Some("mergeDefaults: two arguments required, got 1".to_owned())
+        );
+    }
+}
@@ -0,0 +1,93 @@
+use std::collections::HashMap;
+
+fn unquote(v: &str) -> Result<String, String> {
+    if v.len() >= 2 && v.starts_with('\'') && v.ends_with('\'') {
+        return Ok(v[1..v.len() - 1].to_owned());
+    }
+    if !v.starts_with('"') {
+        let v = match v.find(" #") {
+            Some(i) => &v[..i],
+            None => v,
+        };
+        return Ok(v.trim_end().to_owned());
+    }
+    if v.len() < 2 || !v.ends_with('"') {
+        return Err(format!("unterminated quote in {}", v));
+    }
+    let mut out = String::new();
+    let mut chars = v[1..v.len() - 1].chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some('t') => out.push('\t'),
+            Some(c) => out.push(c),
+            None => out.push('\\'),
+        }
+    }
+    Ok(out)
+}
+
+gtmpl_fn!(
+#[doc = r#"Parse `KEY=VALUE` lines of a `.env` file into a map. Blank lines and lines
+starting with `#` are skipped, as is a leading `export`. Values may be single
+quoted (taken literally) or double quoted (supporting `\n`, `\t` and `\"`)."#]
+fn dotenv_parse(s: String) -> Result<HashMap<String, String>, String> {
+    let mut env = HashMap::new();
+    for (i, line) in s.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = if line.starts_with("export ") {
+            line["export ".len()..].trim_start()
+        } else {
+            line
+        };
+        let mut kv = line.splitn(2, '=');
+        match (kv.next(), kv.next()) {
+            (Some(k), Some(v)) if !k.trim().is_empty() => {
+                let v = unquote(v.trim()).map_err(
+                    |e| format!("line {}: {}", i + 1, e),
+                )?;
+                env.insert(k.trim().to_owned(), v);
+            }
+            _ => return Err(format!("invalid line {}: {}", i + 1, line)),
+        }
+    }
+    Ok(env)
+}
+);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::any::Any;
+    use std::sync::Arc;
+    use gtmpl_value::Value;
+
+    #[test]
+    fn test_dotenv_parse() {
+        let env = "# database\n\
+                   DB_HOST=localhost # local only\n\
+                   \n\
+                   export DB_USER = admin\n\
+                   DB_PASS=\"p#ss \\\"word\\\"\"\n\
+                   GREETING='hello\\nworld'\n";
+        let mut m = HashMap::new();
+        m.insert("DB_HOST".to_owned(), "localhost".to_owned());
+        m.insert("DB_USER".to_owned(), "admin".to_owned());
+        m.insert("DB_PASS".to_owned(), "p#ss \"word\"".to_owned());
+        m.insert("GREETING".to_owned(), "hello\\nworld".to_owned());
+        test_fn!(dotenv_parse, vvarc!(env), m);
+    }
+
+    #[test]
+    fn test_dotenv_parse_invalid() {
+        test_fn_err!(dotenv_parse, vvarc!("NO_VALUE"));
+        test_fn_err!(dotenv_parse, vvarc!("KEY=\"open"));
+    }
+}
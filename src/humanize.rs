@@ -0,0 +1,246 @@
+const BYTE_UNITS: &[&str] = &["KiB", "MiB", "GiB", "TiB", "PiB", "EiB"];
+
+const DURATION_UNITS: &[(i64, &str)] = &[
+    (86_400, "day"),
+    (3_600, "hour"),
+    (60, "minute"),
+    (1, "second"),
+];
+
+const ROMAN: &[(i64, &str)] = &[
+    (1000, "M"),
+    (900, "CM"),
+    (500, "D"),
+    (400, "CD"),
+    (100, "C"),
+    (90, "XC"),
+    (50, "L"),
+    (40, "XL"),
+    (10, "X"),
+    (9, "IX"),
+    (5, "V"),
+    (4, "IV"),
+    (1, "I"),
+];
+
+gtmpl_fn!(
+#[doc = r#"Human readable byte count using binary (1024 based) units:
+`humanizeBytes 1536` yields "1.5 KiB". Counts below 1024 are printed as is, e.g.
+"42 B"."#]
+fn humanize_bytes(n: i64) -> Result<String, String> {
+    if n < 0 {
+        return Err(format!("negative byte count {}", n));
+    }
+    if n < 1024 {
+        return Ok(format!("{} B", n));
+    }
+    let mut size = n as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < BYTE_UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    Ok(format!("{:.1} {}", size, BYTE_UNITS[unit]))
+}
+);
+
+gtmpl_fn!(
+#[doc = r#"Spell out a number of seconds: `humanizeDuration 90061` yields
+"1 day 1 hour 1 minute 1 second". Zero components are omitted."#]
+fn humanize_duration(seconds: i64) -> Result<String, String> {
+    if seconds < 0 {
+        return Err(format!("negative duration {}", seconds));
+    }
+    if seconds == 0 {
+        return Ok(String::from("0 seconds"));
+    }
+    let mut rest = seconds;
+    let mut parts = vec![];
+    for &(size, unit) in DURATION_UNITS {
+        let n = rest / size;
+        rest %= size;
+        match n {
+            0 => {}
+            1 => parts.push(format!("1 {}", unit)),
+            _ => parts.push(format!("{} {}s", n, unit)),
+        }
+    }
+    Ok(parts.join(" "))
+}
+);
+
+gtmpl_fn!(
+#[doc = r#"English ordinal of an integer: `ordinal 2` yields "2nd", `ordinal 11`
+yields "11th"."#]
+fn ordinal(n: i64) -> Result<String, String> {
+    let abs = n.wrapping_abs() as u64;
+    let suffix = match (abs % 10, abs % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    Ok(format!("{}{}", n, suffix))
+}
+);
+
+/// Insert commas between groups of three digits of a (possibly negative)
+/// integer string.
+fn group_thousands(s: &str) -> String {
+    let (sign, digits) = if s.starts_with('-') {
+        ("-", &s[1..])
+    } else {
+        ("", s)
+    };
+    let mut out = String::from(sign);
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            out.push(',');
+        }
+        out.push(c);
+    }
+    out
+}
+
+gtmpl_fn!(
+#[doc = r#"Insert thousands separators into an integer: `commaize 1234567` yields
+"1,234,567"."#]
+fn commaize(n: i64) -> Result<String, String> {
+    Ok(group_thousands(&n.to_string()))
+}
+);
+
+gtmpl_fn!(
+#[doc = r#"Insert thousands separators into the integer part of a float, keeping the
+decimal part: `commaizeFloat 1234.5` yields "1,234.5"."#]
+fn commaize_float(f: f64) -> Result<String, String> {
+    if !f.is_finite() {
+        return Ok(f.to_string());
+    }
+    let s = f.to_string();
+    let mut parts = s.splitn(2, '.');
+    let int = group_thousands(parts.next().unwrap_or(""));
+    Ok(match parts.next() {
+        Some(frac) => format!("{}.{}", int, frac),
+        None => int,
+    })
+}
+);
+
+fn to_roman(mut n: i64) -> String {
+    let mut out = String::new();
+    for &(value, numeral) in ROMAN {
+        while n >= value {
+            out.push_str(numeral);
+            n -= value;
+        }
+    }
+    out
+}
+
+gtmpl_fn!(
+#[doc = r#"Roman numeral of an integer between 1 and 3999: `romanize 1994` yields
+"MCMXCIV"."#]
+fn romanize(n: i64) -> Result<String, String> {
+    if n < 1 || n > 3999 {
+        return Err(format!("{} can not be written as roman numeral", n));
+    }
+    Ok(to_roman(n))
+}
+);
+
+gtmpl_fn!(
+#[doc = r#"Integer value of a roman numeral: `deromanize "MCMXCIV"` yields 1994. Only
+canonical numerals (as produced by `romanize`) are accepted."#]
+fn deromanize(s: String) -> Result<i64, String> {
+    let mut rest = s.as_str();
+    let mut n = 0;
+    for &(value, numeral) in ROMAN {
+        while rest.starts_with(numeral) {
+            n += value;
+            rest = &rest[numeral.len()..];
+        }
+    }
+    if n == 0 || n > 3999 || !rest.is_empty() || to_roman(n) != s {
+        return Err(format!("invalid roman numeral {:?}", s));
+    }
+    Ok(n)
+}
+);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::any::Any;
+    use std::sync::Arc;
+    use gtmpl_value::Value;
+
+    #[test]
+    fn test_humanize_bytes() {
+        test_fn!(humanize_bytes, vvarc!(0), "0 B");
+        test_fn!(humanize_bytes, vvarc!(1023), "1023 B");
+        test_fn!(humanize_bytes, vvarc!(1024), "1.0 KiB");
+        test_fn!(humanize_bytes, vvarc!(1536), "1.5 KiB");
+        test_fn!(humanize_bytes, vvarc!(5_905_580_032i64), "5.5 GiB");
+        test_fn_err!(humanize_bytes, vvarc!(-1));
+    }
+
+    #[test]
+    fn test_humanize_duration() {
+        test_fn!(humanize_duration, vvarc!(90061), "1 day 1 hour 1 minute 1 second");
+        test_fn!(humanize_duration, vvarc!(183_600), "2 days 3 hours");
+        test_fn!(humanize_duration, vvarc!(42), "42 seconds");
+        test_fn!(humanize_duration, vvarc!(0), "0 seconds");
+        test_fn_err!(humanize_duration, vvarc!(-1));
+    }
+
+    #[test]
+    fn test_ordinal() {
+        test_fn!(ordinal, vvarc!(1), "1st");
+        test_fn!(ordinal, vvarc!(2), "2nd");
+        test_fn!(ordinal, vvarc!(3), "3rd");
+        test_fn!(ordinal, vvarc!(4), "4th");
+        test_fn!(ordinal, vvarc!(11), "11th");
+        test_fn!(ordinal, vvarc!(21), "21st");
+        test_fn!(ordinal, vvarc!(113), "113th");
+        test_fn!(ordinal, vvarc!(0), "0th");
+    }
+
+    #[test]
+    fn test_commaize() {
+        test_fn!(commaize, vvarc!(1234567), "1,234,567");
+        test_fn!(commaize, vvarc!(123), "123");
+        test_fn!(commaize, vvarc!(-1234), "-1,234");
+        test_fn!(commaize, vvarc!(-123456), "-123,456");
+        test_fn!(commaize, vvarc!(0), "0");
+    }
+
+    #[test]
+    fn test_commaize_float() {
+        test_fn!(commaize_float, vvarc!(1234567.25), "1,234,567.25");
+        test_fn!(commaize_float, vvarc!(-1234.5), "-1,234.5");
+        test_fn!(commaize_float, vvarc!(1000), "1,000");
+        test_fn!(commaize_float, vvarc!(0.0), "0");
+    }
+
+    #[test]
+    fn test_romanize() {
+        test_fn!(romanize, vvarc!(1994), "MCMXCIV");
+        test_fn!(romanize, vvarc!(3999), "MMMCMXCIX");
+        test_fn!(romanize, vvarc!(4), "IV");
+        test_fn_err!(romanize, vvarc!(0));
+        test_fn_err!(romanize, vvarc!(4000));
+    }
+
+    #[test]
+    fn test_deromanize() {
+        test_fn!(deromanize, vvarc!("MCMXCIV"), 1994i64);
+        test_fn!(deromanize, vvarc!("I"), 1i64);
+        test_fn_err!(deromanize, vvarc!("IIII"));
+        test_fn_err!(deromanize, vvarc!("IC"));
+        test_fn_err!(deromanize, vvarc!("MMMM"));
+        test_fn_err!(deromanize, vvarc!(""));
+        test_fn_err!(deromanize, vvarc!("XA"));
+    }
+}
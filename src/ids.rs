@@ -0,0 +1,124 @@
+use ulid::Ulid;
+use uuid::{self, Uuid};
+
+fn namespace(ns: &str) -> Result<Uuid, String> {
+    match ns {
+        "dns" => Ok(uuid::NAMESPACE_DNS),
+        "url" => Ok(uuid::NAMESPACE_URL),
+        "oid" => Ok(uuid::NAMESPACE_OID),
+        "x500" => Ok(uuid::NAMESPACE_X500),
+        _ => Uuid::parse_str(ns).map_err(|e| format!("invalid namespace {}: {}", ns, e)),
+    }
+}
+
+gtmpl_fn!(
+#[doc = r#"Name based (MD5) UUID, as `uuidv3 NAMESPACE NAME`. The namespace is one of
+"dns", "url", "oid" and "x500" or a UUID. The same inputs always yield the same
+UUID."#]
+fn uuidv3(ns: String, name: String) -> Result<String, String> {
+    Ok(Uuid::new_v3(&namespace(&ns)?, &name).to_string())
+}
+);
+
+gtmpl_fn!(
+#[doc = r#"Name based (SHA-1) UUID, as `uuidv5 NAMESPACE NAME`. Namespaces are given
+as for `uuidv3`."#]
+fn uuidv5(ns: String, name: String) -> Result<String, String> {
+    Ok(Uuid::new_v5(&namespace(&ns)?, &name).to_string())
+}
+);
+
+gtmpl_fn!(
+#[doc = r#"A new ULID: 26 characters of Crockford base 32, sorting by creation time
+(with millisecond precision)."#]
+fn ulid() -> Result<String, String> {
+    Ok(Ulid::new().to_string())
+}
+);
+
+/// Longest NanoID `nanoid` generates.
+const MAX_NANOID_SIZE: i64 = 1024;
+
+gtmpl_fn!(
+#[doc = r#"A new NanoID of the given length, made of the URL safe characters
+`A-Za-z0-9_-`. A size of 0 or less yields the default length of 21, one above
+1024 is an error."#]
+fn nanoid(size: i64) -> Result<String, String> {
+    if size > MAX_NANOID_SIZE {
+        return Err(format!("nanoid: size {} exceeds {}", size, MAX_NANOID_SIZE));
+    }
+    let size = if size <= 0 { 21 } else { size as usize };
+    Ok(::nanoid::generate(size))
+}
+);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::any::Any;
+    use std::sync::Arc;
+    use gtmpl_value::Value;
+
+    #[test]
+    fn test_uuidv3() {
+        test_fn!(
+            uuidv3,
+            vvarc!("dns", "python.org"),
+            "6fa459ea-ee8a-3ca4-894e-db77e160355e"
+        );
+    }
+
+    #[test]
+    fn test_uuidv5() {
+        test_fn!(
+            uuidv5,
+            vvarc!("dns", "python.org"),
+            "886313e1-3b8a-5372-9b90-0c9aee199e5d"
+        );
+        test_fn!(
+            uuidv5,
+            vvarc!("6ba7b811-9dad-11d1-80b4-00c04fd430c8", "https://example.com"),
+            "4fd35a71-71ef-5a55-a9d9-aa75c889a6d0"
+        );
+        test_fn!(
+            uuidv5,
+            vvarc!("url", "https://example.com"),
+            "4fd35a71-71ef-5a55-a9d9-aa75c889a6d0"
+        );
+        test_fn_err!(uuidv5, vvarc!("foo", "bar"));
+    }
+
+    #[test]
+    fn test_ulid() {
+        let crockford = |c: char| "0123456789ABCDEFGHJKMNPQRSTVWXYZ".contains(c);
+        let check = |x: &String| x.len() == 26 && x.chars().all(&crockford);
+        let first = ulid(&vvarc!()).unwrap();
+        let first = first.downcast_ref::<Value>().unwrap().to_string();
+        ::std::thread::sleep(::std::time::Duration::from_millis(2));
+        let second = ulid(&vvarc!()).unwrap();
+        let second = second.downcast_ref::<Value>().unwrap().to_string();
+        assert!(first < second);
+        test_fn_assert!(ulid, vvarc!(), String, check);
+    }
+
+    #[test]
+    fn test_nanoid() {
+        let url_safe = |c: char| c.is_ascii_alphanumeric() || c == '_' || c == '-';
+        let check = |x: &String| x.len() == 10 && x.chars().all(&url_safe);
+        test_fn_assert!(nanoid, vvarc!(10), String, check);
+    }
+
+    #[test]
+    fn test_nanoid_default() {
+        let check = |x: &String| x.len() == 21;
+        test_fn_assert!(nanoid, vvarc!(0), String, check);
+    }
+
+    #[test]
+    fn test_nanoid_too_long() {
+        let check = |x: &String| x.len() == 1024;
+        test_fn_err!(nanoid, vvarc!(1025));
+        test_fn_err!(nanoid, vvarc!(1_000_000_000_000i64));
+        test_fn_assert!(nanoid, vvarc!(1024), String, check);
+    }
+}
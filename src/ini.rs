@@ -0,0 +1,70 @@
+use std::collections::HashMap;
+
+gtmpl_fn!(
+#[doc = r#"Parse an INI document into a map of section => map of key => value.
+Keys before the first `[section]` header go into the `""` section. Lines starting
+with `;` or `#` are comments, keys and values are trimmed."#]
+fn ini_parse(s: String) -> Result<HashMap<String, HashMap<String, String>>, String> {
+    let mut ini: HashMap<String, HashMap<String, String>> = HashMap::new();
+    let mut section = String::new();
+    for (i, line) in s.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with(';') || line.starts_with('#') {
+            continue;
+        }
+        if line.starts_with('[') && line.ends_with(']') {
+            section = line[1..line.len() - 1].trim().to_owned();
+            ini.entry(section.clone()).or_insert_with(HashMap::new);
+            continue;
+        }
+        let mut kv = line.splitn(2, '=');
+        match (kv.next(), kv.next()) {
+            (Some(k), Some(v)) => {
+                ini.entry(section.clone())
+                    .or_insert_with(HashMap::new)
+                    .insert(k.trim().to_owned(), v.trim().to_owned());
+            }
+            _ => return Err(format!("invalid ini line {}: {}", i + 1, line)),
+        }
+    }
+    Ok(ini)
+}
+);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::any::Any;
+    use std::sync::Arc;
+    use gtmpl_value::Value;
+
+    #[test]
+    fn test_ini_parse() {
+        let ini = "top = level\n\
+                   ; comment\n\
+                   [server]\n\
+                   host = localhost\n\
+                   port=8080\n\
+                   \n\
+                   # comment\n\
+                   [client]\n\
+                   name = foo=bar\n";
+        let mut m = HashMap::new();
+        let mut top = HashMap::new();
+        top.insert("top".to_owned(), "level".to_owned());
+        m.insert("".to_owned(), top);
+        let mut server = HashMap::new();
+        server.insert("host".to_owned(), "localhost".to_owned());
+        server.insert("port".to_owned(), "8080".to_owned());
+        m.insert("server".to_owned(), server);
+        let mut client = HashMap::new();
+        client.insert("name".to_owned(), "foo=bar".to_owned());
+        m.insert("client".to_owned(), client);
+        test_fn!(ini_parse, vvarc!(ini), m);
+    }
+
+    #[test]
+    fn test_ini_parse_invalid() {
+        test_fn_err!(ini_parse, vvarc!("[section]\nno value"));
+    }
+}
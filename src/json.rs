@@ -0,0 +1,106 @@
+use std::any::Any;
+use std::sync::Arc;
+
+use data_encoding::BASE64;
+use gtmpl_value::{from_value, Value};
+use serde_json;
+
+use serde_value;
+use utils;
+
+/// Parse a JSON array into a list, as `fromJsonArray STRING`. Unlike a
+/// general JSON parser, any other root (an object, string, number...) is an
+/// error rather than a value of another type.
+pub fn from_json_array(args: &[Arc<Any>]) -> Result<Arc<Any>, String> {
+    utils::arg_count("fromJsonArray", 1, args)?;
+    let arg0 = utils::downcast_arg("fromJsonArray", args, 0)?;
+    let s: String = from_value(arg0).ok_or_else(|| {
+        utils::type_error("fromJsonArray", 1, "string", arg0)
+    })?;
+    let json: serde_json::Value = serde_json::from_str(&s).map_err(|e| {
+        format!("fromJsonArray: invalid JSON: {}", e)
+    })?;
+    if !json.is_array() {
+        return Err("fromJsonArray: JSON root is not an array".to_owned());
+    }
+    Ok(Arc::new(serde_value::from_serde(&json)))
+}
+
+/// Serialize a list as a JSON array, as `toJsonArray LIST`. Anything other
+/// than a list is an error.
+pub fn to_json_array(args: &[Arc<Any>]) -> Result<Arc<Any>, String> {
+    utils::arg_count("toJsonArray", 1, args)?;
+    let arg0 = utils::downcast_arg("toJsonArray", args, 0)?;
+    if let Value::Array(_) = *arg0 {
+        let json = serde_json::to_string(&serde_value::to_serde(arg0)).map_err(|e| {
+            format!("toJsonArray: {}", e)
+        })?;
+        Ok(Arc::new(Value::from(json)))
+    } else {
+        Err(utils::type_error("toJsonArray", 1, "list", arg0))
+    }
+}
+
+/// Serialize a value as JSON and base 64 encode the result, as
+/// `jsonBase64 VALUE`, e.g. for the data of a Kubernetes Secret. Map keys
+/// are sorted, so the output is stable.
+pub fn json_base64(args: &[Arc<Any>]) -> Result<Arc<Any>, String> {
+    utils::arg_count("jsonBase64", 1, args)?;
+    let arg0 = utils::downcast_arg("jsonBase64", args, 0)?;
+    let json = serde_json::to_string(&serde_value::to_serde(arg0)).map_err(|e| {
+        format!("jsonBase64: {}", e)
+    })?;
+    Ok(Arc::new(Value::from(BASE64.encode(json.as_bytes()))))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::any::Any;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+    use gtmpl_value::Value;
+    use strings;
+
+    #[test]
+    fn test_from_json_array() {
+        test_fn!(from_json_array, vvarc!("[1,2,3]"), vec![1i64, 2, 3]);
+        test_fn!(from_json_array, vvarc!("[]"), Vec::<i64>::new());
+        test_fn!(
+            from_json_array,
+            vvarc!(r#"[{"a": "b"}]"#),
+            Value::Array(vec![
+                Value::Map(vec![("a".to_owned(), Value::from("b"))].into_iter().collect()),
+            ])
+        );
+        test_fn_err!(from_json_array, vvarc!(r#"{"a": [1]}"#));
+        test_fn_err!(from_json_array, vvarc!("1"));
+        test_fn_err!(from_json_array, vvarc!("[1,"));
+    }
+
+    #[test]
+    fn test_to_json_array() {
+        test_fn!(to_json_array, vvarc!(vec![1, 2, 3]), "[1,2,3]");
+        test_fn!(to_json_array, vvarc!(vec!["a", "b"]), r#"["a","b"]"#);
+        test_fn!(to_json_array, vvarc!(Vec::<i64>::new()), "[]");
+        let mut m = HashMap::new();
+        m.insert("a".to_owned(), Value::from(1));
+        test_fn_err!(to_json_array, vvarc!(Value::Map(m)));
+        test_fn_err!(to_json_array, vvarc!("[1]"));
+    }
+
+    #[test]
+    fn test_json_base64() {
+        test_fn!(json_base64, vvarc!(vec![1, 2]), "WzEsMl0=");
+
+        let mut m = HashMap::new();
+        m.insert("user".to_owned(), Value::from("admin"));
+        m.insert("ports".to_owned(), Value::from(vec![80, 443]));
+        let m = Value::Map(m);
+        let encoded = json_base64(&vvarc!(m.clone())).unwrap();
+        let encoded: String = from_value(encoded.downcast_ref::<Value>().unwrap()).unwrap();
+        let json = strings::decode(&BASE64, &encoded).unwrap();
+        let decoded = serde_value::from_serde(&serde_json::from_str(&json).unwrap());
+        assert_eq!(decoded, m);
+    }
+}
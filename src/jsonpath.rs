@@ -0,0 +1,153 @@
+//! A minimal JSONPath subset for querying `Value` trees.
+//!
+//! The supported grammar is:
+//!
+//! ```text
+//! path  := "$"? step*
+//! step  := "." key | "[" index "]" | "[*]"
+//! ```
+//!
+//! `key` is a sequence of characters other than `.` and `[`, `index` a
+//! non-negative integer. `[*]` selects all elements of a list, or all values of
+//! a map in key order.
+
+use std::any::Any;
+use std::sync::Arc;
+
+use gtmpl_value::{from_value, Value};
+
+use utils;
+
+enum Step {
+    Key(String),
+    Index(usize),
+    Wildcard,
+}
+
+fn parse(expr: &str) -> Result<Vec<Step>, String> {
+    let mut rest = if expr.starts_with('$') {
+        &expr[1..]
+    } else {
+        expr
+    };
+    let mut steps = vec![];
+    while !rest.is_empty() {
+        if rest.starts_with('.') {
+            let end = rest[1..].find(|c: char| c == '.' || c == '[').map_or(
+                rest.len(),
+                |i| i + 1,
+            );
+            if end == 1 {
+                return Err(format!("empty key in {:?}", expr));
+            }
+            steps.push(Step::Key(rest[1..end].to_owned()));
+            rest = &rest[end..];
+        } else if rest.starts_with('[') {
+            let end = rest.find(']').ok_or_else(
+                || format!("unclosed [ in {:?}", expr),
+            )?;
+            let inner = &rest[1..end];
+            if inner == "*" {
+                steps.push(Step::Wildcard);
+            } else {
+                let i = inner.parse::<usize>().map_err(|_| {
+                    format!("invalid index {:?} in {:?}", inner, expr)
+                })?;
+                steps.push(Step::Index(i));
+            }
+            rest = &rest[end + 1..];
+        } else {
+            return Err(format!("unexpected {:?} in {:?}", rest, expr));
+        }
+    }
+    Ok(steps)
+}
+
+fn select<'a>(v: &'a Value, step: &Step, out: &mut Vec<&'a Value>) {
+    match (step, v) {
+        (&Step::Key(ref k), &Value::Map(ref m)) |
+        (&Step::Key(ref k), &Value::Object(ref m)) => out.extend(m.get(k)),
+        (&Step::Index(i), &Value::Array(ref a)) => out.extend(a.get(i)),
+        (&Step::Wildcard, &Value::Array(ref a)) => out.extend(a.iter()),
+        (&Step::Wildcard, &Value::Map(ref m)) |
+        (&Step::Wildcard, &Value::Object(ref m)) => {
+            out.extend(utils::sorted_entries(m).into_iter().map(|(_, v)| v))
+        }
+        _ => {}
+    }
+}
+
+/// Query a value with a JSONPath expression, as `jsonpath "$.a[0].b" VALUE`.
+/// Yields the list of matched nodes, which is empty if nothing matched. See
+/// the module documentation for the supported grammar.
+pub fn jsonpath(args: &[Arc<Any>]) -> Result<Arc<Any>, String> {
+    utils::arg_count("jsonpath", 2, args)?;
+    let arg0 = utils::downcast_arg("jsonpath", args, 0)?;
+    let expr: String = from_value(arg0).ok_or_else(|| {
+        utils::type_error("jsonpath", 1, "string", arg0)
+    })?;
+    let root = utils::downcast_arg("jsonpath", args, 1)?;
+    let mut nodes = vec![root];
+    for step in parse(&expr)? {
+        let mut next = vec![];
+        for node in nodes {
+            select(node, &step, &mut next);
+        }
+        nodes = next;
+    }
+    Ok(Arc::new(
+        Value::Array(nodes.into_iter().cloned().collect()),
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::any::Any;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+    use gtmpl_value::Value;
+
+    fn map(entries: Vec<(&str, Value)>) -> Value {
+        Value::Map(
+            entries
+                .into_iter()
+                .map(|(k, v)| (k.to_owned(), v))
+                .collect::<HashMap<_, _>>(),
+        )
+    }
+
+    fn doc() -> Value {
+        map(vec![
+            (
+                "a",
+                Value::Array(vec![
+                    map(vec![("b", Value::from(1))]),
+                    map(vec![("b", Value::from(2))]),
+                ]),
+            ),
+            ("c", map(vec![("y", Value::from("y")), ("x", Value::from("x"))])),
+        ])
+    }
+
+    #[test]
+    fn test_jsonpath() {
+        test_fn!(jsonpath, vvarc!("$.a[0].b", doc()), vec![1]);
+        test_fn!(jsonpath, vvarc!("$.a[*].b", doc()), vec![1, 2]);
+        test_fn!(jsonpath, vvarc!(".c[*]", doc()), vec!["x", "y"]);
+        test_fn!(jsonpath, vvarc!("$.a[5].b", doc()), Vec::<i64>::new());
+        test_fn!(jsonpath, vvarc!("$", doc()), vec![doc()]);
+    }
+
+    #[test]
+    fn test_jsonpath_invalid() {
+        test_fn_err!(jsonpath, vvarc!("$.a[", doc()));
+        test_fn_err!(jsonpath, vvarc!("$.a[-1]", doc()));
+        test_fn_err!(jsonpath, vvarc!("$..a", doc()));
+        assert_eq!(
+            jsonpath(&vvarc!(1, doc())).unwrap_err(),
+            "jsonpath: argument 1 expected string, got number"
+        );
+        test_fn_err!(jsonpath, vvarc!("a", doc()));
+    }
+}
@@ -3,13 +3,57 @@ extern crate gtmpl;
 extern crate gtmpl_value;
 #[macro_use]
 extern crate lazy_static;
+extern crate crc;
+extern crate csv as csv_crate;
 extern crate data_encoding;
+extern crate globset;
+#[cfg(feature = "crypto")]
+extern crate hmac;
 extern crate itertools;
+#[macro_use]
+extern crate log;
+#[cfg(feature = "crypto")]
+extern crate jsonwebtoken;
+#[cfg(feature = "crypto")]
+extern crate md5;
+extern crate nanoid;
 extern crate rand;
+extern crate regex as regex_crate;
+extern crate serde_json;
+extern crate serde_yaml;
+#[cfg(feature = "crypto")]
+extern crate sha1;
+#[cfg(feature = "crypto")]
+extern crate sha2;
+extern crate ulid;
+extern crate uuid;
 
+#[macro_use]
 mod utils;
+mod aliases;
+mod builder;
+mod conversion;
+mod crypto;
+mod csv;
+mod defaults;
+mod dicts;
+mod dotenv;
+mod humanize;
+mod ids;
+mod ini;
+mod json;
+mod jsonpath;
+mod lists;
+mod math;
+mod printf;
+mod regex;
+mod serde_value;
 mod strings;
+mod tpl;
+mod yaml;
 
+pub use builder::Builder;
+pub use utils::seed_rng;
 
 use gtmpl::Func;
 
@@ -20,6 +64,179 @@ lazy_static! {
     pub static ref SPRIG: HashMap<String, Func> = {
         let mut m = HashMap::new();
         m.insert("split".to_owned(), strings::split as Func);
+        m.insert("int".to_owned(), conversion::int64 as Func);
+        m.insert("int64".to_owned(), conversion::int64 as Func);
+        m.insert("float64".to_owned(), conversion::float64 as Func);
+        m.insert("add".to_owned(), math::add as Func);
+        m.insert("add1".to_owned(), math::add1 as Func);
+        m.insert("sub".to_owned(), math::sub as Func);
+        m.insert("mul".to_owned(), math::mul as Func);
+        m.insert("div".to_owned(), math::div as Func);
+        m.insert("max".to_owned(), math::max as Func);
+        m.insert("biggest".to_owned(), math::max as Func);
+        m.insert("min".to_owned(), math::min as Func);
+        m.insert("mod".to_owned(), math::modulo as Func);
+        m.insert("pow".to_owned(), math::pow as Func);
+        m.insert("sqrt".to_owned(), math::sqrt as Func);
+        m.insert("floor".to_owned(), math::floor as Func);
+        m.insert("ceil".to_owned(), math::ceil as Func);
+        m.insert("round".to_owned(), math::round as Func);
+        m.insert("randInt".to_owned(), math::rand_int as Func);
+        m.insert("until".to_owned(), math::until as Func);
+        m.insert("untilStep".to_owned(), math::until_step as Func);
+        m.insert("gcd".to_owned(), math::gcd as Func);
+        m.insert("lcm".to_owned(), math::lcm as Func);
+        m.insert("clamp".to_owned(), math::clamp as Func);
+        m.insert("randChoice".to_owned(), lists::rand_choice as Func);
+        m.insert("shuffleList".to_owned(), lists::shuffle_list as Func);
+        m.insert("sample".to_owned(), lists::sample as Func);
+        m.insert("list".to_owned(), lists::list as Func);
+        m.insert("first".to_owned(), lists::first as Func);
+        m.insert("last".to_owned(), lists::last as Func);
+        m.insert("rest".to_owned(), lists::rest as Func);
+        m.insert("initial".to_owned(), lists::initial as Func);
+        m.insert("append".to_owned(), lists::append as Func);
+        m.insert("prepend".to_owned(), lists::prepend as Func);
+        m.insert("concat".to_owned(), lists::concat as Func);
+        m.insert("quoteList".to_owned(), lists::quote_list as Func);
+        m.insert("squoteList".to_owned(), lists::squote_list as Func);
+        m.insert(
+            "base64encodeList".to_owned(),
+            lists::base64encode_list as Func,
+        );
+        m.insert(
+            "base64decodeList".to_owned(),
+            lists::base64decode_list as Func,
+        );
+        m.insert("matchesGlob".to_owned(), strings::matches_glob as Func);
+        m.insert("trimQuotes".to_owned(), strings::trim_quotes as Func);
+        m.insert("titleWords".to_owned(), strings::title_words as Func);
+        m.insert("untitleWords".to_owned(), strings::untitle_words as Func);
+        m.insert("wordCount".to_owned(), strings::word_count as Func);
+        m.insert("charCount".to_owned(), strings::char_count as Func);
+        m.insert("reverse".to_owned(), strings::reverse_str as Func);
+        m.insert("capitalize".to_owned(), strings::capitalize as Func);
+        m.insert("uncapitalize".to_owned(), strings::uncapitalize as Func);
+        m.insert(
+            "base64encodeMime".to_owned(),
+            strings::base64encode_mime as Func,
+        );
+        m.insert("toIntList".to_owned(), lists::to_int_list as Func);
+        m.insert("toFloatList".to_owned(), lists::to_float_list as Func);
+        m.insert("csvParse".to_owned(), csv::csv_parse as Func);
+        m.insert("csvJoin".to_owned(), csv::csv_join as Func);
+        m.insert("iniParse".to_owned(), ini::ini_parse as Func);
+        m.insert("dotenvParse".to_owned(), dotenv::dotenv_parse as Func);
+        m.insert("printf".to_owned(), printf::printf as Func);
+        m.insert("humanizeBytes".to_owned(), humanize::humanize_bytes as Func);
+        m.insert(
+            "humanizeDuration".to_owned(),
+            humanize::humanize_duration as Func,
+        );
+        m.insert("ordinal".to_owned(), humanize::ordinal as Func);
+        m.insert("commaize".to_owned(), humanize::commaize as Func);
+        m.insert("commaizeFloat".to_owned(), humanize::commaize_float as Func);
+        m.insert("romanize".to_owned(), humanize::romanize as Func);
+        m.insert("deromanize".to_owned(), humanize::deromanize as Func);
+        m.insert("isBlank".to_owned(), strings::is_blank as Func);
+        m.insert("coalesceStr".to_owned(), strings::coalesce_str as Func);
+        m.insert("trimReport".to_owned(), strings::trim_report as Func);
+        m.insert("substrNeg".to_owned(), strings::substr_neg as Func);
+        m.insert("repeatSep".to_owned(), strings::repeat_sep as Func);
+        m.insert("splitList".to_owned(), strings::split_list as Func);
+        m.insert("lastIndexOf".to_owned(), strings::last_index_of as Func);
+        m.insert("splitLines".to_owned(), strings::split_lines as Func);
+        m.insert("wrap".to_owned(), strings::wrap as Func);
+        m.insert("dedent".to_owned(), strings::dedent as Func);
+        m.insert("reindent".to_owned(), strings::reindent as Func);
+        m.insert("toBool".to_owned(), conversion::to_bool as Func);
+        m.insert("all".to_owned(), defaults::all as Func);
+        m.insert("any".to_owned(), defaults::any as Func);
+        m.insert("defaultIf".to_owned(), defaults::default_if as Func);
+        m.insert("getPath".to_owned(), dicts::get_path as Func);
+        m.insert("setPath".to_owned(), dicts::set_path as Func);
+        m.insert("hasKeyPath".to_owned(), dicts::has_key_path as Func);
+        m.insert("mapKeysPrefix".to_owned(), dicts::map_keys_prefix as Func);
+        m.insert(
+            "mapValuesToString".to_owned(),
+            dicts::map_values_to_string as Func,
+        );
+        m.insert("omitEmpty".to_owned(), dicts::omit_empty as Func);
+        m.insert("jsonpath".to_owned(), jsonpath::jsonpath as Func);
+        m.insert("mergeArrays".to_owned(), dicts::merge_arrays as Func);
+        m.insert("indentTabs".to_owned(), strings::indent_tabs as Func);
+        m.insert("nindentTabs".to_owned(), strings::nindent_tabs as Func);
+        m.insert(
+            "base32crockfordEncode".to_owned(),
+            strings::base32crockford_encode as Func,
+        );
+        m.insert(
+            "base32crockfordDecode".to_owned(),
+            strings::base32crockford_decode as Func,
+        );
+        m.insert("uuidv3".to_owned(), ids::uuidv3 as Func);
+        m.insert("uuidv5".to_owned(), ids::uuidv5 as Func);
+        m.insert("snakecase".to_owned(), strings::snakecase as Func);
+        m.insert("kebabcase".to_owned(), strings::kebabcase as Func);
+        m.insert("camelcase".to_owned(), strings::camelcase as Func);
+        m.insert("ulid".to_owned(), ids::ulid as Func);
+        m.insert("upper".to_owned(), strings::upper as Func);
+        m.insert("lower".to_owned(), strings::lower as Func);
+        m.insert("title".to_owned(), strings::title as Func);
+        m.insert("swapcase".to_owned(), strings::swapcase as Func);
+        m.insert("nanoid".to_owned(), ids::nanoid as Func);
+        m.insert("repeat".to_owned(), strings::repeat as Func);
+        m.insert("nospace".to_owned(), strings::nospace as Func);
+        m.insert("crc32".to_owned(), crypto::crc32 as Func);
+        m.insert("wrapWith".to_owned(), strings::wrap_with as Func);
+        #[cfg(feature = "crypto")]
+        m.insert("md5sum".to_owned(), crypto::md5sum as Func);
+        m.insert("quote".to_owned(), strings::quote as Func);
+        m.insert("squote".to_owned(), strings::squote as Func);
+        #[cfg(feature = "crypto")]
+        m.insert("hmacSha256".to_owned(), crypto::hmac_sha256 as Func);
+        m.insert("indent".to_owned(), strings::indent as Func);
+        m.insert("nindent".to_owned(), strings::nindent as Func);
+        #[cfg(feature = "crypto")]
+        m.insert("signJwt".to_owned(), crypto::sign_jwt as Func);
+        #[cfg(feature = "crypto")]
+        m.insert("decodeJwt".to_owned(), crypto::decode_jwt as Func);
+        m.insert("cat".to_owned(), strings::cat as Func);
+        m.insert("tpl".to_owned(), tpl::tpl as Func);
+        m.insert("regexMatch".to_owned(), regex::regex_match as Func);
+        m.insert("regexFindAll".to_owned(), regex::regex_find_all as Func);
+        m.insert("regexReplaceAll".to_owned(), regex::regex_replace_all as Func);
+        m.insert(
+            "regexReplaceAllLiteral".to_owned(),
+            regex::regex_replace_all_literal as Func,
+        );
+        m.insert("required".to_owned(), defaults::required as Func);
+        m.insert("regexFind".to_owned(), regex::regex_find as Func);
+        m.insert("regexSplit".to_owned(), regex::regex_split as Func);
+        m.insert("fromJsonArray".to_owned(), json::from_json_array as Func);
+        m.insert("toJsonArray".to_owned(), json::to_json_array as Func);
+        #[cfg(feature = "crypto")]
+        m.insert("sha1sum".to_owned(), crypto::sha1sum as Func);
+        #[cfg(feature = "crypto")]
+        m.insert("sha256sum".to_owned(), crypto::sha256sum as Func);
+        m.insert("jsonBase64".to_owned(), json::json_base64 as Func);
+        m.insert("adler32sum".to_owned(), crypto::adler32sum as Func);
+        m.insert("hasKeyCI".to_owned(), dicts::has_key_ci as Func);
+        m.insert("getOr".to_owned(), dicts::get_or as Func);
+        m.insert("valuesWhere".to_owned(), dicts::values_where as Func);
+        m.insert("mergeDefaults".to_owned(), dicts::merge_defaults as Func);
+        m.insert("toYaml".to_owned(), yaml::to_yaml as Func);
+        m.insert("fromYaml".to_owned(), yaml::from_yaml as Func);
+        m.insert("b32dec".to_owned(), strings::base32decode as Func);
+        m.insert("b32enc".to_owned(), strings::base32encode as Func);
+        m.insert("b64dec".to_owned(), strings::base64decode as Func);
+        m.insert("b64enc".to_owned(), strings::base64encode as Func);
+        m.insert("substr".to_owned(), strings::substring as Func);
+        m.insert("base32decode".to_owned(), aliases::base32decode as Func);
+        m.insert("base32encode".to_owned(), aliases::base32encode as Func);
+        m.insert("base64decode".to_owned(), aliases::base64decode as Func);
+        m.insert("base64encode".to_owned(), aliases::base64encode as Func);
+        m.insert("substring".to_owned(), aliases::substring as Func);
         m
     };
 }
@@ -0,0 +1,408 @@
+use std::any::Any;
+use std::sync::Arc;
+
+use data_encoding::BASE64;
+use rand::Rng;
+
+use gtmpl_value::{from_value, Value};
+
+use conversion;
+use strings;
+use utils;
+
+/// Argument `i` of the function named `name`, which must be a list.
+fn list_arg<'a>(name: &str, args: &'a [Arc<Any>], i: usize) -> Result<&'a Vec<Value>, String> {
+    let arg = utils::downcast_arg(name, args, i)?;
+    if let Value::Array(ref list) = *arg {
+        Ok(list)
+    } else {
+        Err(utils::type_error(name, i + 1, "list", arg))
+    }
+}
+
+/// A list of the arguments, as `list A B ...`. `list` without arguments
+/// yields an empty list.
+pub fn list(args: &[Arc<Any>]) -> Result<Arc<Any>, String> {
+    (0..args.len())
+        .map(|i| utils::downcast_arg("list", args, i).map(|v| v.clone()))
+        .collect::<Result<Vec<_>, _>>()
+        .map(|l| Arc::new(Value::Array(l)) as Arc<Any>)
+}
+
+/// Pick a random element from a list, as `randChoice LIST`. An empty list is
+/// an error.
+pub fn rand_choice(args: &[Arc<Any>]) -> Result<Arc<Any>, String> {
+    utils::arg_count("randChoice", 1, args)?;
+    let list = list_arg("randChoice", args, 0)?;
+    utils::with_rng(|rng| rng.choose(list).cloned())
+        .map(|v| Arc::new(v) as Arc<Any>)
+        .ok_or_else(|| String::from("unable to choose from an empty list"))
+}
+
+/// Randomly reorder a copy of a list, as `shuffleList LIST`.
+pub fn shuffle_list(args: &[Arc<Any>]) -> Result<Arc<Any>, String> {
+    utils::arg_count("shuffleList", 1, args)?;
+    let mut list = list_arg("shuffleList", args, 0)?.clone();
+    utils::with_rng(|rng| rng.shuffle(&mut list));
+    Ok(Arc::new(Value::Array(list)))
+}
+
+/// Pick `n` distinct random elements from a list, as `sample N LIST`. The
+/// whole list is returned (in random order) if `n` is not smaller than its
+/// length. A negative `n` is an error.
+pub fn sample(args: &[Arc<Any>]) -> Result<Arc<Any>, String> {
+    utils::arg_count("sample", 2, args)?;
+    let arg0 = utils::downcast_arg("sample", args, 0)?;
+    let n: i64 = from_value(arg0).ok_or_else(|| {
+        utils::type_error("sample", 1, "integer", arg0)
+    })?;
+    if n < 0 {
+        return Err(format!("sample: unable to sample {} elements", n));
+    }
+    let mut list = list_arg("sample", args, 1)?.clone();
+    let n = (n as u64).min(list.len() as u64) as usize;
+    utils::with_rng(|rng| for i in 0..n {
+        let j = rng.gen_range(i, list.len());
+        list.swap(i, j);
+    });
+    list.truncate(n);
+    Ok(Arc::new(Value::Array(list)))
+}
+
+/// The first element of a list, as `first LIST`. Nil for an empty list.
+pub fn first(args: &[Arc<Any>]) -> Result<Arc<Any>, String> {
+    utils::arg_count("first", 1, args)?;
+    let list = list_arg("first", args, 0)?;
+    Ok(Arc::new(list.first().cloned().unwrap_or(Value::Nil)))
+}
+
+/// The last element of a list, as `last LIST`. Nil for an empty list.
+pub fn last(args: &[Arc<Any>]) -> Result<Arc<Any>, String> {
+    utils::arg_count("last", 1, args)?;
+    let list = list_arg("last", args, 0)?;
+    Ok(Arc::new(list.last().cloned().unwrap_or(Value::Nil)))
+}
+
+/// All but the first element of a list, as `rest LIST`.
+pub fn rest(args: &[Arc<Any>]) -> Result<Arc<Any>, String> {
+    utils::arg_count("rest", 1, args)?;
+    let list = list_arg("rest", args, 0)?;
+    let rest = list.split_first().map_or(&[][..], |(_, rest)| rest);
+    Ok(Arc::new(Value::Array(rest.to_vec())))
+}
+
+/// All but the last element of a list, as `initial LIST`.
+pub fn initial(args: &[Arc<Any>]) -> Result<Arc<Any>, String> {
+    utils::arg_count("initial", 1, args)?;
+    let list = list_arg("initial", args, 0)?;
+    let initial = list.split_last().map_or(&[][..], |(_, initial)| initial);
+    Ok(Arc::new(Value::Array(initial.to_vec())))
+}
+
+/// A copy of a list with an element added to the end, as
+/// `append LIST ITEM`.
+pub fn append(args: &[Arc<Any>]) -> Result<Arc<Any>, String> {
+    utils::arg_count("append", 2, args)?;
+    let mut list = list_arg("append", args, 0)?.clone();
+    list.push(utils::downcast_arg("append", args, 1)?.clone());
+    Ok(Arc::new(Value::Array(list)))
+}
+
+/// A copy of a list with an element added to the front, as
+/// `prepend LIST ITEM`.
+pub fn prepend(args: &[Arc<Any>]) -> Result<Arc<Any>, String> {
+    utils::arg_count("prepend", 2, args)?;
+    let list = list_arg("prepend", args, 0)?;
+    let mut prepended = Vec::with_capacity(list.len() + 1);
+    prepended.push(utils::downcast_arg("prepend", args, 1)?.clone());
+    prepended.extend(list.iter().cloned());
+    Ok(Arc::new(Value::Array(prepended)))
+}
+
+/// All lists joined into one, as `concat LIST...`.
+pub fn concat(args: &[Arc<Any>]) -> Result<Arc<Any>, String> {
+    let mut joined = vec![];
+    for i in 0..args.len() {
+        joined.extend(list_arg("concat", args, i)?.iter().cloned());
+    }
+    Ok(Arc::new(Value::Array(joined)))
+}
+
+fn map_list<F>(name: &str, args: &[Arc<Any>], f: F) -> Result<Arc<Any>, String>
+where
+    F: Fn(&Value) -> Value,
+{
+    utils::arg_count(name, 1, args)?;
+    let list = list_arg(name, args, 0)?;
+    Ok(Arc::new(Value::Array(list.iter().map(f).collect())))
+}
+
+fn try_map_list<F>(name: &str, args: &[Arc<Any>], f: F) -> Result<Arc<Any>, String>
+where
+    F: Fn(&Value) -> Result<Value, String>,
+{
+    utils::arg_count(name, 1, args)?;
+    let list = list_arg(name, args, 0)?;
+    list.iter()
+        .enumerate()
+        .map(|(i, v)| f(v).map_err(|e| format!("element {}: {}", i, e)))
+        .collect::<Result<Vec<_>, _>>()
+        .map(|l| Arc::new(Value::Array(l)) as Arc<Any>)
+}
+
+/// Wrap every element of a list in double quotes, as `quoteList LIST`.
+/// `quoteList ["a","b"]` yields `["\"a\"","\"b\""]`.
+pub fn quote_list(args: &[Arc<Any>]) -> Result<Arc<Any>, String> {
+    map_list("quoteList", args, |v| Value::from(strings::go_quote(&v.to_string())))
+}
+
+/// Wrap every element of a list in single quotes, as `squoteList LIST`.
+pub fn squote_list(args: &[Arc<Any>]) -> Result<Arc<Any>, String> {
+    map_list("squoteList", args, |v| Value::from(format!("'{}'", v)))
+}
+
+/// Base 64 encode every element of a list, as `base64encodeList LIST`.
+pub fn base64encode_list(args: &[Arc<Any>]) -> Result<Arc<Any>, String> {
+    map_list("base64encodeList", args, |v| {
+        Value::from(BASE64.encode(v.to_string().as_bytes()))
+    })
+}
+
+/// Base 64 decode every element of a list, as `base64decodeList LIST`. The
+/// error names the index of the first element that fails to decode.
+pub fn base64decode_list(args: &[Arc<Any>]) -> Result<Arc<Any>, String> {
+    try_map_list("base64decodeList", args, |v| {
+        strings::decode(&BASE64, &v.to_string()).map(Value::from)
+    })
+}
+
+/// Convert every element of a list to an `int64`, as `toIntList LIST`.
+///
+/// Unlike Sprig, which turns unconvertible elements into 0, this fails like
+/// `int64` does, naming the index of the offending element.
+pub fn to_int_list(args: &[Arc<Any>]) -> Result<Arc<Any>, String> {
+    try_map_list("toIntList", args, |v| conversion::value_to_i64(v).map(Value::from))
+}
+
+/// Convert every element of a list to a `float64`, as `toFloatList LIST`.
+/// Errors are handled as in `toIntList`.
+pub fn to_float_list(args: &[Arc<Any>]) -> Result<Arc<Any>, String> {
+    try_map_list("toFloatList", args, |v| conversion::value_to_f64(v).map(Value::from))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::any::Any;
+    use std::sync::Arc;
+    use gtmpl_value::Value;
+
+    fn pick(seed: usize) -> Value {
+        utils::seed_rng(seed);
+        let v = vvarc!(vec!["a", "b", "c", "d", "e"]);
+        let ret = rand_choice(&v).unwrap();
+        ret.downcast_ref::<Value>().unwrap().clone()
+    }
+
+    #[test]
+    fn test_rand_choice() {
+        let check = |x: &String| ["a", "b", "c", "d", "e"].contains(&x.as_str());
+        test_fn_assert!(
+            rand_choice,
+            vvarc!(vec!["a", "b", "c", "d", "e"]),
+            String,
+            check
+        );
+    }
+
+    #[test]
+    fn test_rand_choice_seeded() {
+        assert_eq!(pick(42), Value::from("d"));
+        assert_eq!(pick(42), pick(42));
+    }
+
+    #[test]
+    fn test_rand_choice_empty() {
+        test_fn_err!(rand_choice, vvarc!(Vec::<String>::new()));
+        assert_eq!(
+            rand_choice(&vvarc!()).unwrap_err(),
+            "randChoice: one argument required, got 0"
+        );
+    }
+
+    fn shuffled(seed: usize) -> Vec<String> {
+        utils::seed_rng(seed);
+        let v = vvarc!(vec!["a", "b", "c", "d", "e"]);
+        let ret = shuffle_list(&v).unwrap();
+        if let Some(&Value::Array(ref list)) = ret.downcast_ref::<Value>() {
+            return list.iter().map(|v| v.to_string()).collect();
+        }
+        panic!("shuffleList must return an Array");
+    }
+
+    #[test]
+    fn test_shuffle_list() {
+        let mut list = shuffled(7);
+        list.sort();
+        assert_eq!(list, vec!["a", "b", "c", "d", "e"]);
+        test_fn!(shuffle_list, vvarc!(Vec::<String>::new()), Vec::<String>::new());
+        assert_eq!(
+            shuffle_list(&vvarc!("abc")).unwrap_err(),
+            "shuffleList: argument 1 expected list, got string"
+        );
+    }
+
+    #[test]
+    fn test_shuffle_list_seeded() {
+        assert_eq!(shuffled(7), shuffled(7));
+    }
+
+    fn sampled(seed: usize, n: i64) -> Vec<String> {
+        utils::seed_rng(seed);
+        let v = vvarc!(n, vec!["a", "b", "c", "d", "e"]);
+        let ret = sample(&v).unwrap();
+        if let Some(&Value::Array(ref list)) = ret.downcast_ref::<Value>() {
+            return list.iter().map(|v| v.to_string()).collect();
+        }
+        panic!("sample must return an Array");
+    }
+
+    #[test]
+    fn test_sample() {
+        let mut list = sampled(3, 3);
+        assert_eq!(list.len(), 3);
+        list.sort();
+        list.dedup();
+        assert_eq!(list.len(), 3);
+        let mut list = sampled(3, 10);
+        list.sort();
+        assert_eq!(list, vec!["a", "b", "c", "d", "e"]);
+        assert!(sampled(3, 0).is_empty());
+        test_fn_err!(sample, vvarc!(-1, vec!["a"]));
+        assert_eq!(
+            sample(&vvarc!("x", vec!["a"])).unwrap_err(),
+            "sample: argument 1 expected integer, got string"
+        );
+    }
+
+    #[test]
+    fn test_sample_seeded() {
+        assert_eq!(sampled(3, 3), sampled(3, 3));
+    }
+
+    #[test]
+    fn test_list() {
+        test_fn!(list, vvarc!(), Vec::<i64>::new());
+        test_fn!(list, vvarc!(1, 2, 3), vec![1i64, 2, 3]);
+        test_fn!(
+            list,
+            vvarc!(1, "a", vec![true], Value::Nil),
+            Value::Array(vec![
+                Value::from(1),
+                Value::from("a"),
+                Value::Array(vec![Value::from(true)]),
+                Value::Nil,
+            ])
+        );
+    }
+
+    #[test]
+    fn test_first() {
+        test_fn!(first, vvarc!(vec![1, 2, 3]), 1i64);
+        test_fn!(first, vvarc!(vec!["a"]), "a");
+        test_fn!(first, vvarc!(Vec::<i64>::new()), Value::Nil);
+        test_fn_err!(first, vvarc!("abc"));
+    }
+
+    #[test]
+    fn test_last() {
+        test_fn!(last, vvarc!(vec![1, 2, 3]), 3i64);
+        test_fn!(last, vvarc!(vec!["a"]), "a");
+        test_fn!(last, vvarc!(Vec::<i64>::new()), Value::Nil);
+    }
+
+    #[test]
+    fn test_rest() {
+        test_fn!(rest, vvarc!(vec![1, 2, 3]), vec![2i64, 3]);
+        test_fn!(rest, vvarc!(vec![1]), Vec::<i64>::new());
+        test_fn!(rest, vvarc!(Vec::<i64>::new()), Vec::<i64>::new());
+    }
+
+    #[test]
+    fn test_initial() {
+        test_fn!(initial, vvarc!(vec![1, 2, 3]), vec![1i64, 2]);
+        test_fn!(initial, vvarc!(vec![1]), Vec::<i64>::new());
+        test_fn!(initial, vvarc!(Vec::<i64>::new()), Vec::<i64>::new());
+    }
+
+    #[test]
+    fn test_append() {
+        test_fn!(append, vvarc!(vec![1, 2], 3), vec![1i64, 2, 3]);
+        test_fn!(append, vvarc!(Vec::<i64>::new(), "a"), vec!["a"]);
+        test_fn_err!(append, vvarc!(1, 2));
+        assert_eq!(
+            append(&vvarc!(vec![1])).err(),
+            Some("append: two arguments required, got 1".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_prepend() {
+        test_fn!(prepend, vvarc!(vec![2, 3], 1), vec![1i64, 2, 3]);
+        test_fn!(prepend, vvarc!(Vec::<i64>::new(), "a"), vec!["a"]);
+    }
+
+    #[test]
+    fn test_concat() {
+        test_fn!(concat, vvarc!(vec![1], vec![2, 3]), vec![1i64, 2, 3]);
+        test_fn!(concat, vvarc!(vec![1], Vec::<i64>::new(), vec![2], vec![3]), vec![1i64, 2, 3]);
+        test_fn!(concat, vvarc!(), Vec::<i64>::new());
+        test_fn_err!(concat, vvarc!(vec![1], 2));
+    }
+
+    #[test]
+    fn test_quote_list() {
+        test_fn!(quote_list, vvarc!(vec!["a", "b"]), vec!["\"a\"", "\"b\""]);
+        test_fn!(quote_list, vvarc!(vec!["a\"b"]), vec!["\"a\\\"b\""]);
+    }
+
+    #[test]
+    fn test_squote_list() {
+        test_fn!(squote_list, vvarc!(vec!["a", "b"]), vec!["'a'", "'b'"]);
+    }
+
+    #[test]
+    fn test_base64encode_list() {
+        test_fn!(
+            base64encode_list,
+            vvarc!(vec!["Hello", "World!"]),
+            vec!["SGVsbG8=", "V29ybGQh"]
+        );
+    }
+
+    #[test]
+    fn test_base64decode_list() {
+        test_fn!(
+            base64decode_list,
+            vvarc!(vec!["SGVsbG8=", "V29ybGQh"]),
+            vec!["Hello", "World!"]
+        );
+        let v = vvarc!(vec!["SGVsbG8=", "%%%"]);
+        let err = base64decode_list(&v).unwrap_err();
+        assert!(err.starts_with("element 1:"));
+    }
+
+    #[test]
+    fn test_to_int_list() {
+        test_fn!(to_int_list, vvarc!(vec!["1", "2", " 3 "]), vec![1i64, 2, 3]);
+        let v = vvarc!(vec!["1", "2", "x"]);
+        let err = to_int_list(&v).unwrap_err();
+        assert!(err.starts_with("element 2:"));
+    }
+
+    #[test]
+    fn test_to_float_list() {
+        test_fn!(to_float_list, vvarc!(vec!["1", "2.5"]), vec![1f64, 2.5]);
+        test_fn_err!(to_float_list, vvarc!(vec!["1", "2", "x"]));
+    }
+}
@@ -0,0 +1,533 @@
+use std::any::Any;
+use std::sync::Arc;
+
+use gtmpl_value::{from_value, Value};
+use rand::Rng;
+
+use utils;
+
+/// A number argument, keeping track of whether it was given as an integer.
+#[derive(Clone, Copy)]
+enum Num {
+    Int(i64),
+    Float(f64),
+}
+
+impl Num {
+    fn as_f64(&self) -> f64 {
+        match *self {
+            Num::Int(i) => i as f64,
+            Num::Float(f) => f,
+        }
+    }
+
+    fn into_value(self) -> Value {
+        match self {
+            Num::Int(i) => Value::from(i),
+            Num::Float(f) => Value::from(f),
+        }
+    }
+}
+
+/// Argument `i` of the function named `name`, which must be a number.
+fn num_arg(name: &str, args: &[Arc<Any>], i: usize) -> Result<Num, String> {
+    let arg = utils::downcast_arg(name, args, i)?;
+    if let Value::Number(ref n) = *arg {
+        if let Some(int) = n.as_i64() {
+            return Ok(Num::Int(int));
+        }
+        if let Some(f) = n.as_f64() {
+            return Ok(Num::Float(f));
+        }
+    }
+    Err(utils::type_error(name, i + 1, "number", arg))
+}
+
+/// All arguments of the function named `name` as integers.
+fn int_args(name: &str, args: &[Arc<Any>]) -> Result<Vec<i64>, String> {
+    (0..args.len())
+        .map(|i| {
+            let arg = utils::downcast_arg(name, args, i)?;
+            from_value(arg).ok_or_else(|| utils::type_error(name, i + 1, "integer", arg))
+        })
+        .collect()
+}
+
+/// Sum of the arguments, as `add A B ...`. `add` without arguments yields 0.
+pub fn add(args: &[Arc<Any>]) -> Result<Arc<Any>, String> {
+    let sum = int_args("add", args)?.into_iter().fold(Some(0i64), |acc, i| {
+        acc.and_then(|acc| acc.checked_add(i))
+    });
+    sum.map(|s| Arc::new(Value::from(s)) as Arc<Any>)
+        .ok_or_else(|| String::from("add: sum overflows int64"))
+}
+
+gtmpl_fn!(
+#[doc = r#"An integer plus one, as `add1 N`."#]
+fn add1(n: i64) -> Result<i64, String> {
+    n.checked_add(1).ok_or_else(
+        || format!("{} plus 1 overflows int64", n),
+    )
+}
+);
+
+gtmpl_fn!(
+#[doc = r#"Difference of two integers, as `sub A B`."#]
+fn sub(a: i64, b: i64) -> Result<i64, String> {
+    a.checked_sub(b).ok_or_else(
+        || format!("{} minus {} overflows int64", a, b),
+    )
+}
+);
+
+/// Product of the arguments, as `mul A B ...`. `mul` without arguments
+/// yields 1.
+pub fn mul(args: &[Arc<Any>]) -> Result<Arc<Any>, String> {
+    let product = int_args("mul", args)?.into_iter().fold(Some(1i64), |acc, i| {
+        acc.and_then(|acc| acc.checked_mul(i))
+    });
+    product
+        .map(|p| Arc::new(Value::from(p)) as Arc<Any>)
+        .ok_or_else(|| String::from("mul: product overflows int64"))
+}
+
+/// The largest argument, as `max A B ...`. At least one argument is required.
+/// Also available as `biggest`.
+pub fn max(args: &[Arc<Any>]) -> Result<Arc<Any>, String> {
+    int_args("max", args)?
+        .into_iter()
+        .max()
+        .map(|m| Arc::new(Value::from(m)) as Arc<Any>)
+        .ok_or_else(|| String::from("max: at least one argument required"))
+}
+
+/// The smallest argument, as `min A B ...`. At least one argument is required.
+pub fn min(args: &[Arc<Any>]) -> Result<Arc<Any>, String> {
+    int_args("min", args)?
+        .into_iter()
+        .min()
+        .map(|m| Arc::new(Value::from(m)) as Arc<Any>)
+        .ok_or_else(|| String::from("min: at least one argument required"))
+}
+
+gtmpl_fn!(
+#[doc = r#"Integer division, as `div A B`. Like Go's `/` the result is truncated
+towards zero: `div -7 2` yields `-3`."#]
+fn div(a: i64, b: i64) -> Result<i64, String> {
+    if b == 0 {
+        return Err(String::from("integer divide by zero"));
+    }
+    a.checked_div(b).ok_or_else(
+        || format!("{} divided by {} overflows int64", a, b),
+    )
+}
+);
+
+gtmpl_fn!(
+#[doc = r#"Modulo, as `mod A B`. Like Go's `%` the result carries the sign of the
+dividend: `mod -7 3` yields `-1` and `mod 7 -3` yields `1`."#]
+fn modulo(a: i64, b: i64) -> Result<i64, String> {
+    if b == 0 {
+        return Err(String::from("integer divide by zero"));
+    }
+    Ok(a.wrapping_rem(b))
+}
+);
+
+/// Raise `base` to the power of `exp`, as `pow BASE EXP`. The result is an
+/// integer if both arguments are integers and `exp` is not negative,
+/// otherwise a float.
+pub fn pow(args: &[Arc<Any>]) -> Result<Arc<Any>, String> {
+    utils::arg_count("pow", 2, args)?;
+    let base = num_arg("pow", args, 0)?;
+    let exp = num_arg("pow", args, 1)?;
+    let ret = match (base, exp) {
+        (Num::Int(b), Num::Int(e)) if e >= 0 => {
+            if e > i64::from(u32::max_value()) {
+                return Err(String::from("exponent too large"));
+            }
+            b.checked_pow(e as u32).map(Num::Int).ok_or_else(|| {
+                format!("{} to the power of {} overflows int64", b, e)
+            })?
+        }
+        (b, e) => Num::Float(b.as_f64().powf(e.as_f64())),
+    };
+    Ok(Arc::new(ret.into_value()))
+}
+
+gtmpl_fn!(
+#[doc = r#"Square root of a number. Negative numbers are an error."#]
+fn sqrt(x: f64) -> Result<f64, String> {
+    if x < 0.0 {
+        return Err(format!("square root of negative number {}", x));
+    }
+    Ok(x.sqrt())
+}
+);
+
+gtmpl_fn!(
+#[doc = r#"The largest integer less than or equal to a number, as a float."#]
+fn floor(x: f64) -> Result<f64, String> {
+    Ok(x.floor())
+}
+);
+
+gtmpl_fn!(
+#[doc = r#"The smallest integer greater than or equal to a number, as a float."#]
+fn ceil(x: f64) -> Result<f64, String> {
+    Ok(x.ceil())
+}
+);
+
+/// Round a number to a number of decimal places, as `round X PLACES` or
+/// `round X PLACES ROUND_ON`. Like Sprig, a fractional part of at least
+/// `ROUND_ON` (default `0.5`) rounds away from zero: `round 3.14159 2`
+/// yields `3.14` and `round -2.5 0` yields `-3`.
+pub fn round(args: &[Arc<Any>]) -> Result<Arc<Any>, String> {
+    if args.len() != 2 && args.len() != 3 {
+        return Err(format!("round: two or three arguments required, got {}", args.len()));
+    }
+    let x = num_arg("round", args, 0)?.as_f64();
+    let places_arg = utils::downcast_arg("round", args, 1)?;
+    let places: i64 = from_value(places_arg).ok_or_else(|| {
+        utils::type_error("round", 2, "integer", places_arg)
+    })?;
+    let round_on = if args.len() == 3 {
+        num_arg("round", args, 2)?.as_f64()
+    } else {
+        0.5
+    };
+    if places < -308 || places > 308 {
+        return Err(format!("round: {} decimal places out of range", places));
+    }
+    let pow = 10f64.powi(places as i32);
+    let digits = x.abs() * pow;
+    let rounded = if digits.fract() >= round_on {
+        digits.ceil()
+    } else {
+        digits.floor()
+    };
+    Ok(Arc::new(Value::from((rounded / pow).copysign(x))))
+}
+
+gtmpl_fn!(
+#[doc = r#"A random integer in `[min, max)`, as `randInt MIN MAX`."#]
+fn rand_int(min: i64, max: i64) -> Result<i64, String> {
+    if min >= max {
+        return Err(format!("randInt: empty range [{}, {})", min, max));
+    }
+    Ok(utils::with_rng(|rng| rng.gen_range(min, max)))
+}
+);
+
+/// Longest list `until` and `untilStep` build.
+const MAX_RANGE_LEN: u64 = 1 << 20;
+
+/// `start`, `start + step`, ... up to but excluding `stop`, for the function
+/// named `name`. Empty if `step` is zero or points away from `stop`, an error
+/// if longer than `MAX_RANGE_LEN`.
+fn step_range(name: &str, start: i64, stop: i64, step: i64) -> Result<Vec<i64>, String> {
+    if step == 0 || (step > 0 && start >= stop) || (step < 0 && start <= stop) {
+        return Ok(vec![]);
+    }
+    // The distance always fits into a u64, even where it overflows an i64.
+    let span = if step > 0 {
+        stop.wrapping_sub(start) as u64
+    } else {
+        start.wrapping_sub(stop) as u64
+    };
+    let len = (span - 1) / abs_u64(step) + 1;
+    if len > MAX_RANGE_LEN {
+        return Err(format!("{}: {} integers exceed {}", name, len, MAX_RANGE_LEN));
+    }
+    Ok(
+        (0..len as i64)
+            .map(|k| start.wrapping_add(k.wrapping_mul(step)))
+            .collect(),
+    )
+}
+
+gtmpl_fn!(
+#[doc = r#"The integers from 0 up to but excluding `n`, as `until N`, e.g. to
+`range` over: `until 3` yields `[0 1 2]`. A negative `n` counts down. More than
+1048576 integers are an error."#]
+fn until(n: i64) -> Result<Vec<i64>, String> {
+    step_range("until", 0, n, if n < 0 { -1 } else { 1 })
+}
+);
+
+gtmpl_fn!(
+#[doc = r#"The integers from `start` up to but excluding `stop` in steps of
+`step`, as `untilStep START STOP STEP`: `untilStep 3 6 1` yields `[3 4 5]`.
+A zero step, or one pointing away from `stop`, yields an empty list. More than
+1048576 integers are an error."#]
+fn until_step(start: i64, stop: i64, step: i64) -> Result<Vec<i64>, String> {
+    step_range("untilStep", start, stop, step)
+}
+);
+
+fn euclid(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        let t = a % b;
+        a = b;
+        b = t;
+    }
+    a
+}
+
+fn abs_u64(i: i64) -> u64 {
+    i.wrapping_abs() as u64
+}
+
+gtmpl_fn!(
+#[doc = r#"Greatest common divisor of two integers, always non-negative.
+`gcd 0 N` yields the absolute value of N."#]
+fn gcd(a: i64, b: i64) -> Result<i64, String> {
+    let g = euclid(abs_u64(a), abs_u64(b));
+    if g > i64::max_value() as u64 {
+        return Err(format!("gcd of {} and {} overflows int64", a, b));
+    }
+    Ok(g as i64)
+}
+);
+
+gtmpl_fn!(
+#[doc = r#"Least common multiple of two integers, always non-negative.
+`lcm 0 N` yields 0."#]
+fn lcm(a: i64, b: i64) -> Result<i64, String> {
+    if a == 0 || b == 0 {
+        return Ok(0);
+    }
+    let (x, y) = (abs_u64(a), abs_u64(b));
+    (x / euclid(x, y))
+        .checked_mul(y)
+        .and_then(|l| if l > i64::max_value() as u64 {
+            None
+        } else {
+            Some(l as i64)
+        })
+        .ok_or_else(|| format!("lcm of {} and {} overflows int64", a, b))
+}
+);
+
+/// Bound a number into `[min, max]`, as `clamp MIN MAX X`. The result is an
+/// integer if all arguments are integers, otherwise a float.
+pub fn clamp(args: &[Arc<Any>]) -> Result<Arc<Any>, String> {
+    utils::arg_count("clamp", 3, args)?;
+    let min = num_arg("clamp", args, 0)?;
+    let max = num_arg("clamp", args, 1)?;
+    let x = num_arg("clamp", args, 2)?;
+    if min.as_f64() > max.as_f64() {
+        return Err(String::from("clamp: min must not be greater than max"));
+    }
+    let ret = match (min, max, x) {
+        (Num::Int(min), Num::Int(max), Num::Int(x)) => Num::Int(x.max(min).min(max)),
+        (min, max, x) => Num::Float(x.as_f64().max(min.as_f64()).min(max.as_f64())),
+    };
+    Ok(Arc::new(ret.into_value()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::any::Any;
+    use std::sync::Arc;
+    use gtmpl_value::Value;
+
+    #[test]
+    fn test_add() {
+        test_fn!(add, vvarc!(1, 2, 3), 6i64);
+        test_fn!(add, vvarc!(1, -2), -1i64);
+        test_fn!(add, vvarc!(5), 5i64);
+        test_fn!(add, vvarc!(), 0i64);
+        test_fn_err!(add, vvarc!(9223372036854775807i64, 1));
+        test_fn_err!(add, vvarc!(1, "2"));
+    }
+
+    #[test]
+    fn test_add1() {
+        test_fn!(add1, vvarc!(1), 2i64);
+        test_fn!(add1, vvarc!(-1), 0i64);
+        test_fn_err!(add1, vvarc!(9223372036854775807i64));
+    }
+
+    #[test]
+    fn test_sub() {
+        test_fn!(sub, vvarc!(5, 3), 2i64);
+        test_fn!(sub, vvarc!(-5, 3), -8i64);
+        test_fn!(sub, vvarc!(3, -5), 8i64);
+    }
+
+    #[test]
+    fn test_mul() {
+        test_fn!(mul, vvarc!(2, 3, 4), 24i64);
+        test_fn!(mul, vvarc!(-2, 3), -6i64);
+        test_fn!(mul, vvarc!(), 1i64);
+        test_fn_err!(mul, vvarc!(9223372036854775807i64, 2));
+    }
+
+    #[test]
+    fn test_max() {
+        test_fn!(max, vvarc!(1, 5, 3), 5i64);
+        test_fn!(max, vvarc!(7), 7i64);
+        test_fn!(max, vvarc!(-1, -5, -3), -1i64);
+        test_fn_err!(max, vvarc!());
+    }
+
+    #[test]
+    fn test_min() {
+        test_fn!(min, vvarc!(1, 5, 3), 1i64);
+        test_fn!(min, vvarc!(7), 7i64);
+        test_fn!(min, vvarc!(-1, -5, -3), -5i64);
+        test_fn_err!(min, vvarc!());
+    }
+
+    #[test]
+    fn test_div() {
+        test_fn!(div, vvarc!(7, 2), 3i64);
+        test_fn!(div, vvarc!(-7, 2), -3i64);
+        test_fn!(div, vvarc!(7, -2), -3i64);
+        test_fn_err!(div, vvarc!(7, 0));
+    }
+
+    #[test]
+    fn test_modulo() {
+        test_fn!(modulo, vvarc!(7, 3), 1i64);
+        test_fn!(modulo, vvarc!(-7, 3), -1i64);
+        test_fn!(modulo, vvarc!(7, -3), 1i64);
+        test_fn!(modulo, vvarc!(-7, -3), -1i64);
+        test_fn_err!(modulo, vvarc!(7, 0));
+    }
+
+    #[test]
+    fn test_pow() {
+        test_fn!(pow, vvarc!(2, 10), 1024i64);
+        test_fn!(pow, vvarc!(2, 0), 1i64);
+        test_fn!(pow, vvarc!(2, -1), 0.5f64);
+        test_fn!(pow, vvarc!(1.5, 2), 2.25f64);
+        test_fn_err!(pow, vvarc!(10, 100));
+        assert_eq!(
+            pow(&vvarc!(2, "x")).unwrap_err(),
+            "pow: argument 2 expected number, got string"
+        );
+    }
+
+    #[test]
+    fn test_sqrt() {
+        test_fn!(sqrt, vvarc!(16), 4f64);
+        test_fn!(sqrt, vvarc!(2.25), 1.5f64);
+        test_fn_err!(sqrt, vvarc!(-1));
+    }
+
+    #[test]
+    fn test_floor() {
+        test_fn!(floor, vvarc!(1.5), 1f64);
+        test_fn!(floor, vvarc!(-1.5), -2f64);
+        test_fn!(floor, vvarc!(2), 2f64);
+    }
+
+    #[test]
+    fn test_ceil() {
+        test_fn!(ceil, vvarc!(1.5), 2f64);
+        test_fn!(ceil, vvarc!(-1.5), -1f64);
+        test_fn!(ceil, vvarc!(2), 2f64);
+    }
+
+    #[test]
+    fn test_round() {
+        test_fn!(round, vvarc!(3.14159, 2), 3.14f64);
+        test_fn!(round, vvarc!(-3.14159, 2), -3.14f64);
+        test_fn!(round, vvarc!(3.7, 0), 4f64);
+        test_fn!(round, vvarc!(2.5, 0), 3f64);
+        test_fn!(round, vvarc!(-2.5, 0), -3f64);
+        test_fn!(round, vvarc!(0.125, 2), 0.13f64);
+        test_fn!(round, vvarc!(1234, -2), 1200f64);
+        test_fn!(round, vvarc!(2.5, 0, 0.6), 2f64);
+        test_fn_err!(round, vvarc!(2.5));
+        test_fn_err!(round, vvarc!(2.5, -9223372036854775808i64));
+        assert_eq!(
+            round(&vvarc!(2.5)).unwrap_err(),
+            "round: two or three arguments required, got 1"
+        );
+        assert_eq!(
+            round(&vvarc!("x", 2)).unwrap_err(),
+            "round: argument 1 expected number, got string"
+        );
+    }
+
+    #[test]
+    fn test_rand_int() {
+        for _ in 0..1000 {
+            let ret = rand_int(&vvarc!(-3, 5)).unwrap();
+            let i: i64 = from_value(ret.downcast_ref::<Value>().unwrap()).unwrap();
+            assert!(i >= -3 && i < 5, "{} not in [-3, 5)", i);
+        }
+        test_fn!(rand_int, vvarc!(7, 8), 7i64);
+        test_fn_err!(rand_int, vvarc!(5, 5));
+        test_fn_err!(rand_int, vvarc!(5, 3));
+    }
+
+    #[test]
+    fn test_until() {
+        test_fn!(until, vvarc!(3), vec![0i64, 1, 2]);
+        test_fn!(until, vvarc!(-3), vec![0i64, -1, -2]);
+        test_fn!(until, vvarc!(0), Vec::<i64>::new());
+        assert_eq!(
+            until(&vvarc!(9223372036854775807i64)).unwrap_err(),
+            "until: 9223372036854775807 integers exceed 1048576"
+        );
+        test_fn_err!(until, vvarc!(-9223372036854775808i64));
+    }
+
+    #[test]
+    fn test_until_step() {
+        test_fn!(until_step, vvarc!(3, 6, 1), vec![3i64, 4, 5]);
+        test_fn!(until_step, vvarc!(0, 10, 4), vec![0i64, 4, 8]);
+        test_fn!(until_step, vvarc!(6, 3, -1), vec![6i64, 5, 4]);
+        test_fn!(until_step, vvarc!(3, 6, 0), Vec::<i64>::new());
+        test_fn!(until_step, vvarc!(3, 6, -1), Vec::<i64>::new());
+        test_fn!(until_step, vvarc!(6, 3, 1), Vec::<i64>::new());
+        test_fn!(
+            until_step,
+            vvarc!(9223372036854775806i64, 9223372036854775807i64, 5),
+            vec![9223372036854775806i64]
+        );
+        test_fn!(
+            until_step,
+            vvarc!(-9223372036854775808i64, 9223372036854775807i64, 9223372036854775807i64),
+            vec![-9223372036854775808i64, -1, 9223372036854775806]
+        );
+        test_fn_err!(until_step, vvarc!(0, 9223372036854775807i64, 1));
+        test_fn_err!(until_step, vvarc!(0, 1 << 21, 1));
+    }
+
+    #[test]
+    fn test_gcd() {
+        test_fn!(gcd, vvarc!(12, 18), 6i64);
+        test_fn!(gcd, vvarc!(-12, 18), 6i64);
+        test_fn!(gcd, vvarc!(0, 5), 5i64);
+        test_fn!(gcd, vvarc!(0, 0), 0i64);
+    }
+
+    #[test]
+    fn test_lcm() {
+        test_fn!(lcm, vvarc!(4, 6), 12i64);
+        test_fn!(lcm, vvarc!(-4, 6), 12i64);
+        test_fn!(lcm, vvarc!(0, 6), 0i64);
+        test_fn_err!(lcm, vvarc!(9223372036854775807i64, 2));
+    }
+
+    #[test]
+    fn test_clamp() {
+        test_fn!(clamp, vvarc!(0, 10, -5), 0i64);
+        test_fn!(clamp, vvarc!(0, 10, 5), 5i64);
+        test_fn!(clamp, vvarc!(0, 10, 15), 10i64);
+        test_fn!(clamp, vvarc!(0, 1, 0.5), 0.5f64);
+        test_fn!(clamp, vvarc!(0, 1.5, 2), 1.5f64);
+        test_fn_err!(clamp, vvarc!(10, 0, 5));
+        assert_eq!(
+            clamp(&vvarc!(0, 10)).unwrap_err(),
+            "clamp: three arguments required, got 2"
+        );
+    }
+}
@@ -0,0 +1,216 @@
+use std::any::Any;
+use std::iter::Peekable;
+use std::str::Chars;
+use std::sync::Arc;
+
+use gtmpl_value::{from_value, Value};
+
+use utils;
+
+/// A parsed `%[flags][width][.precision]verb` directive.
+#[derive(Default)]
+struct Spec {
+    minus: bool,
+    plus: bool,
+    zero: bool,
+    width: Option<usize>,
+    precision: Option<usize>,
+}
+
+/// Largest width or precision accepted, so that a format string can't make
+/// the output arbitrarily large.
+const MAX_WIDTH: usize = 4096;
+
+/// Parse a width or precision, failing with "printf: {what} too large" if it
+/// exceeds `MAX_WIDTH`.
+fn number(chars: &mut Peekable<Chars>, what: &str) -> Result<Option<usize>, String> {
+    let mut n = None;
+    while let Some(d) = chars.peek().and_then(|c| c.to_digit(10)) {
+        n = n.unwrap_or(0usize)
+            .checked_mul(10)
+            .and_then(|n| n.checked_add(d as usize))
+            .filter(|&n| n <= MAX_WIDTH)
+            .map(Some)
+            .ok_or_else(|| format!("printf: {} too large", what))?;
+        chars.next();
+    }
+    Ok(n)
+}
+
+fn parse_spec(chars: &mut Peekable<Chars>) -> Result<Spec, String> {
+    let mut spec = Spec::default();
+    loop {
+        match chars.peek() {
+            Some(&'-') => spec.minus = true,
+            Some(&'+') => spec.plus = true,
+            Some(&'0') => spec.zero = true,
+            _ => break,
+        }
+        chars.next();
+    }
+    spec.width = number(chars, "width")?;
+    if chars.peek() == Some(&'.') {
+        chars.next();
+        spec.precision = Some(number(chars, "precision")?.unwrap_or(0));
+    }
+    Ok(spec)
+}
+
+fn pad(s: String, spec: &Spec, numeric: bool) -> String {
+    let len = s.chars().count();
+    let width = match spec.width {
+        Some(w) if w > len => w,
+        _ => return s,
+    };
+    let fill = width - len;
+    if spec.minus {
+        format!("{}{}", s, " ".repeat(fill))
+    } else if spec.zero && numeric {
+        let sign = if s.starts_with('-') || s.starts_with('+') { 1 } else { 0 };
+        format!("{}{}{}", &s[..sign], "0".repeat(fill), &s[sign..])
+    } else {
+        format!("{}{}", " ".repeat(fill), s)
+    }
+}
+
+fn sign(s: String, spec: &Spec) -> String {
+    if spec.plus && !s.starts_with('-') {
+        format!("+{}", s)
+    } else {
+        s
+    }
+}
+
+fn format_arg(verb: char, spec: &Spec, arg: &Value) -> Result<String, String> {
+    let mismatch = || format!("%{} can not format {}", verb, arg);
+    let int = || -> Result<i64, String> { from_value::<i64>(arg).ok_or_else(&mismatch) };
+    match verb {
+        'v' => Ok(pad(arg.to_string(), spec, false)),
+        's' => {
+            if let Value::String(ref s) = *arg {
+                let s = match spec.precision {
+                    Some(p) => s.chars().take(p).collect(),
+                    None => s.clone(),
+                };
+                Ok(pad(s, spec, false))
+            } else {
+                Err(mismatch())
+            }
+        }
+        'd' => Ok(pad(sign(int()?.to_string(), spec), spec, true)),
+        'x' | 'X' => {
+            let s = match *arg {
+                Value::String(ref s) => s.bytes().map(|b| format!("{:02x}", b)).collect(),
+                _ => {
+                    let i = int()?;
+                    if i < 0 {
+                        format!("-{:x}", i.wrapping_neg() as u64)
+                    } else {
+                        format!("{:x}", i)
+                    }
+                }
+            };
+            let s = if verb == 'X' { s.to_uppercase() } else { s };
+            Ok(pad(s, spec, true))
+        }
+        'f' => {
+            let f = match *arg {
+                Value::Number(ref n) => n.as_f64().ok_or_else(&mismatch)?,
+                _ => return Err(mismatch()),
+            };
+            let s = format!("{:.*}", spec.precision.unwrap_or(6), f);
+            Ok(pad(sign(s, spec), spec, true))
+        }
+        _ => Err(format!("unsupported verb %{}", verb)),
+    }
+}
+
+/// Format arguments like Go's `fmt.Sprintf`, as `printf FORMAT ARGS...`.
+///
+/// Supported verbs are `%s`, `%d`, `%f`, `%v`, `%x`, `%X` and `%%`, with the
+/// `-`, `+` and `0` flags, width and precision (each at most 4096). Unlike
+/// Go, a verb not matching its argument, as well as missing or surplus
+/// arguments, are errors.
+pub fn printf(args: &[Arc<Any>]) -> Result<Arc<Any>, String> {
+    if args.is_empty() {
+        return Err(String::from("printf: at least one argument required"));
+    }
+    let mut values = Vec::with_capacity(args.len());
+    for i in 0..args.len() {
+        values.push(utils::downcast_arg("printf", args, i)?);
+    }
+    let format: String = from_value(values[0]).ok_or_else(|| {
+        utils::type_error("printf", 1, "string", values[0])
+    })?;
+    let mut params = values[1..].iter();
+    let mut out = String::new();
+    let mut chars = format.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            out.push(c);
+            continue;
+        }
+        if chars.peek() == Some(&'%') {
+            chars.next();
+            out.push('%');
+            continue;
+        }
+        let spec = parse_spec(&mut chars)?;
+        let verb = chars.next().ok_or_else(
+            || "format ends with an incomplete verb".to_owned(),
+        )?;
+        let arg = params.next().ok_or_else(
+            || format!("missing argument for %{}", verb),
+        )?;
+        out.push_str(&format_arg(verb, &spec, arg)?);
+    }
+    if params.len() > 0 {
+        return Err(format!("{} surplus argument(s)", params.len()));
+    }
+    Ok(Arc::new(Value::from(out)))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::any::Any;
+    use std::sync::Arc;
+    use gtmpl_value::Value;
+
+    #[test]
+    fn test_printf() {
+        test_fn!(printf, vvarc!("%05d", 42), "00042");
+        test_fn!(printf, vvarc!("%-5d|", -42), "-42  |");
+        test_fn!(printf, vvarc!("%+d", 42), "+42");
+        test_fn!(printf, vvarc!("%s and %v", "foo", 1.5), "foo and 1.5");
+        test_fn!(printf, vvarc!("%v %v", true, "bar"), "true bar");
+        test_fn!(printf, vvarc!("%6.2f|%.1f", 3.14159, 2), "  3.14|2.0");
+        test_fn!(printf, vvarc!("%x %X %04x", 255, 255, "a"), "ff FF 0061");
+        test_fn!(printf, vvarc!("%.2s %%", "foobar"), "fo %");
+    }
+
+    #[test]
+    fn test_printf_err() {
+        test_fn_err!(printf, vvarc!("%d", "foo"));
+        test_fn_err!(printf, vvarc!("%s", 42));
+        test_fn_err!(printf, vvarc!("%s %s", "foo"));
+        test_fn_err!(printf, vvarc!("%s", "foo", "bar"));
+        test_fn_err!(printf, vvarc!("%q", "foo"));
+        assert_eq!(
+            printf(&vvarc!(42)).unwrap_err(),
+            "printf: argument 1 expected string, got number"
+        );
+    }
+
+    #[test]
+    fn test_printf_width_limit() {
+        test_fn!(printf, vvarc!("%4096d", 1), format!("{:>4096}", 1));
+        test_fn_err!(printf, vvarc!("%4097d", 1));
+        test_fn_err!(printf, vvarc!("%99999999999999999999d", 1));
+        test_fn_err!(printf, vvarc!("%.99999999999999999999f", 1.5));
+        assert_eq!(
+            printf(&vvarc!("%99999999999999999999d", 1)).err(),
+            Some("printf: width too large".to_owned())
+        );
+    }
+}
@@ -0,0 +1,123 @@
+use regex_crate::{NoExpand, Regex};
+
+fn compile(pattern: &str) -> Result<Regex, String> {
+    Regex::new(pattern).map_err(|e| format!("invalid regex {:?}: {}", pattern, e))
+}
+
+gtmpl_fn!(
+#[doc = r#"Whether a string contains a match of the regular expression:
+`regexMatch "^[a-z]+$" "foo"` yields true."#]
+fn regex_match(pattern: String, s: String) -> Result<bool, String> {
+    Ok(compile(&pattern)?.is_match(&s))
+}
+);
+
+gtmpl_fn!(
+#[doc = r#"The first `n` matches of the regular expression in a string, as
+`regexFindAll PATTERN N STRING`. A negative `n` returns all matches."#]
+fn regex_find_all(pattern: String, n: i64, s: String) -> Result<Vec<String>, String> {
+    let re = compile(&pattern)?;
+    let matches = re.find_iter(&s).map(|m| m.as_str().to_owned());
+    Ok(if n < 0 {
+        matches.collect()
+    } else {
+        matches.take(n as usize).collect()
+    })
+}
+);
+
+gtmpl_fn!(
+#[doc = r#"The first match of the regular expression in a string, or an empty
+string if there is none: `regexFind "[0-9]+" "a1b22c"` yields "1"."#]
+fn regex_find(pattern: String, s: String) -> Result<String, String> {
+    Ok(compile(&pattern)?
+        .find(&s)
+        .map(|m| m.as_str().to_owned())
+        .unwrap_or_default())
+}
+);
+
+gtmpl_fn!(
+#[doc = r#"Split a string around matches of the regular expression into at most
+`n` pieces, as `regexSplit PATTERN STRING N`. A negative `n` returns all
+pieces."#]
+fn regex_split(pattern: String, s: String, n: i64) -> Result<Vec<String>, String> {
+    let re = compile(&pattern)?;
+    Ok(if n < 0 {
+        re.split(&s).map(str::to_owned).collect()
+    } else {
+        re.splitn(&s, n as usize).map(str::to_owned).collect()
+    })
+}
+);
+
+gtmpl_fn!(
+#[doc = r#"Replace every match of the regular expression, as
+`regexReplaceAll PATTERN REPLACEMENT STRING`. Within the replacement `$1` or
+`${1}` expands to the text of the matching group."#]
+fn regex_replace_all(pattern: String, repl: String, s: String) -> Result<String, String> {
+    Ok(compile(&pattern)?.replace_all(&s, repl.as_str()).into_owned())
+}
+);
+
+gtmpl_fn!(
+#[doc = r#"Replace every match of the regular expression with the replacement
+inserted verbatim, as `regexReplaceAllLiteral PATTERN REPLACEMENT STRING`."#]
+fn regex_replace_all_literal(pattern: String, repl: String, s: String) -> Result<String, String> {
+    Ok(compile(&pattern)?.replace_all(&s, NoExpand(&repl)).into_owned())
+}
+);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::any::Any;
+    use std::sync::Arc;
+    use gtmpl_value::Value;
+
+    #[test]
+    fn test_regex_match() {
+        test_fn!(regex_match, vvarc!("^[a-z]+$", "foo"), true);
+        test_fn!(regex_match, vvarc!("^[a-z]+$", "foo1"), false);
+        test_fn_err!(regex_match, vvarc!("[a-z", "foo"));
+    }
+
+    #[test]
+    fn test_regex_find_all() {
+        test_fn!(regex_find_all, vvarc!("[0-9]+", -1, "a1b22c"), vec!["1", "22"]);
+        test_fn!(regex_find_all, vvarc!("[0-9]+", 1, "a1b22c"), vec!["1"]);
+        test_fn!(regex_find_all, vvarc!("[0-9]+", 0, "a1b22c"), Vec::<String>::new());
+        test_fn_err!(regex_find_all, vvarc!("(", -1, "a1b22c"));
+    }
+
+    #[test]
+    fn test_regex_find() {
+        test_fn!(regex_find, vvarc!("[0-9]+", "a1b22c"), "1");
+        test_fn!(regex_find, vvarc!("[0-9]+", "abc"), "");
+        test_fn_err!(regex_find, vvarc!("(", "abc"));
+    }
+
+    #[test]
+    fn test_regex_split() {
+        test_fn!(regex_split, vvarc!(",", "a,b,c", 2), vec!["a", "b,c"]);
+        test_fn!(regex_split, vvarc!(",", "a,b,c", -1), vec!["a", "b", "c"]);
+        test_fn!(regex_split, vvarc!(",", "a,b,c", 0), Vec::<String>::new());
+        test_fn!(regex_split, vvarc!("x*", "a,b", 1), vec!["a,b"]);
+        test_fn!(regex_split, vvarc!(";", "a,b,c", -1), vec!["a,b,c"]);
+        test_fn_err!(regex_split, vvarc!("(", "a,b,c", -1));
+    }
+
+    #[test]
+    fn test_regex_replace_all() {
+        test_fn!(regex_replace_all, vvarc!("(a)(b)", "${2}${1}", "ab"), "ba");
+        test_fn!(regex_replace_all, vvarc!("a(x*)b", "${1}W", "-ab-axxb-"), "-W-xxW-");
+        test_fn_err!(regex_replace_all, vvarc!("(", "", "ab"));
+    }
+
+    #[test]
+    fn test_regex_replace_all_literal() {
+        test_fn!(regex_replace_all_literal, vvarc!("(a)(b)", "${2}${1}", "ab"), "${2}${1}");
+        test_fn!(regex_replace_all_literal, vvarc!("a(x*)b", "$1", "-ab-axxb-"), "-$1-$1-");
+        test_fn_err!(regex_replace_all_literal, vvarc!("(", "", "ab"));
+    }
+}
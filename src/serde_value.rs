@@ -0,0 +1,135 @@
+//! Conversion between gtmpl `Value`s and `serde_json::Value`s, shared by all
+//! functions reading or writing JSON (including JWT claims) or YAML.
+
+use gtmpl_value::Value;
+use serde_json::{self, Map, Number};
+
+use utils;
+
+/// Convert a gtmpl `Value` into a `serde_json::Value`. Map entries are
+/// emitted sorted by key.
+///
+/// `NoValue`, `Nil` and functions become `null`, as do floats which JSON
+/// can't represent (NaN and infinity).
+pub fn to_serde(v: &Value) -> serde_json::Value {
+    match *v {
+        Value::NoValue | Value::Nil | Value::Function(_) => serde_json::Value::Null,
+        Value::Bool(b) => serde_json::Value::Bool(b),
+        Value::String(ref s) => serde_json::Value::String(s.clone()),
+        Value::Number(ref n) => {
+            if let Some(i) = n.as_i64() {
+                serde_json::Value::Number(Number::from(i))
+            } else if let Some(u) = n.as_u64() {
+                serde_json::Value::Number(Number::from(u))
+            } else {
+                n.as_f64()
+                    .and_then(Number::from_f64)
+                    .map(serde_json::Value::Number)
+                    .unwrap_or(serde_json::Value::Null)
+            }
+        }
+        Value::Array(ref a) => serde_json::Value::Array(a.iter().map(to_serde).collect()),
+        Value::Object(ref m) |
+        Value::Map(ref m) => {
+            serde_json::Value::Object(
+                utils::sorted_entries(m)
+                    .into_iter()
+                    .map(|(k, v)| (k.clone(), to_serde(v)))
+                    .collect::<Map<_, _>>(),
+            )
+        }
+    }
+}
+
+/// Convert a `serde_json::Value` into a gtmpl `Value`. JSON objects become
+/// `Value::Map`s.
+pub fn from_serde(v: &serde_json::Value) -> Value {
+    match *v {
+        serde_json::Value::Null => Value::Nil,
+        serde_json::Value::Bool(b) => Value::Bool(b),
+        serde_json::Value::String(ref s) => Value::String(s.clone()),
+        serde_json::Value::Number(ref n) => {
+            if let Some(i) = n.as_i64() {
+                Value::from(i)
+            } else if let Some(u) = n.as_u64() {
+                Value::from(u)
+            } else {
+                n.as_f64().map(Value::from).unwrap_or(Value::Nil)
+            }
+        }
+        serde_json::Value::Array(ref a) => Value::Array(a.iter().map(from_serde).collect()),
+        serde_json::Value::Object(ref m) => {
+            Value::Map(
+                m.iter()
+                    .map(|(k, v)| (k.clone(), from_serde(v)))
+                    .collect(),
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn round_trip(v: Value) {
+        assert_eq!(from_serde(&to_serde(&v)), v);
+    }
+
+    #[test]
+    fn test_nil() {
+        assert_eq!(to_serde(&Value::Nil), serde_json::Value::Null);
+        assert_eq!(to_serde(&Value::NoValue), serde_json::Value::Null);
+        round_trip(Value::Nil);
+    }
+
+    #[test]
+    fn test_bool() {
+        round_trip(Value::from(true));
+        round_trip(Value::from(false));
+    }
+
+    #[test]
+    fn test_string() {
+        round_trip(Value::from("foobar"));
+    }
+
+    #[test]
+    fn test_number() {
+        round_trip(Value::from(-42i64));
+        round_trip(Value::from(18446744073709551615u64));
+        round_trip(Value::from(1.5f64));
+        assert_eq!(
+            to_serde(&Value::from(::std::f64::NAN)),
+            serde_json::Value::Null
+        );
+    }
+
+    #[test]
+    fn test_array() {
+        round_trip(Value::Array(
+            vec![Value::from(1i64), Value::from("a"), Value::Nil],
+        ));
+    }
+
+    #[test]
+    fn test_map() {
+        let mut m = HashMap::new();
+        m.insert("a".to_owned(), Value::from(1i64));
+        m.insert("b".to_owned(), Value::Array(vec![Value::from(true)]));
+        round_trip(Value::Map(m));
+    }
+
+    #[test]
+    fn test_map_order() {
+        let mut m = HashMap::new();
+        for k in &["d", "b", "a", "e", "c"] {
+            m.insert(k.to_string(), Value::from(*k));
+        }
+        let v = Value::Map(m);
+        let json = to_serde(&v).to_string();
+        assert_eq!(json, r#"{"a":"a","b":"b","c":"c","d":"d","e":"e"}"#);
+        assert_eq!(json, to_serde(&v.clone()).to_string());
+    }
+}
@@ -1,16 +1,42 @@
 use std::any::Any;
 use std::cmp;
 use std::collections::HashMap;
+use std::fmt::Write;
 use std::str;
 use std::sync::Arc;
 
-use itertools;
-use data_encoding::{BASE32, BASE64};
+use data_encoding::{Encoding, Specification, BASE32, BASE64};
+use globset::Glob;
 
 use gtmpl_value::{from_value, Value};
 
 use utils;
 
+lazy_static! {
+    /// Crockford's base 32 alphabet (no I, L, O or U), without padding. Decoding
+    /// is case insensitive, maps I and L to 1 and O to 0, and ignores hyphens.
+    static ref BASE32_CROCKFORD: Encoding = {
+        let mut spec = Specification::new();
+        spec.symbols.push_str("0123456789ABCDEFGHJKMNPQRSTVWXYZ");
+        spec.translate.from.push_str("abcdefghjkmnpqrstvwxyzIiLlOo");
+        spec.translate.to.push_str("ABCDEFGHJKMNPQRSTVWXYZ111100");
+        spec.ignore.push_str("-");
+        spec.encoding().unwrap()
+    };
+}
+
+/// Decode `s` with the given encoding into a UTF-8 string.
+pub fn decode(encoding: &Encoding, s: &str) -> Result<String, String> {
+    encoding
+        .decode(s.as_bytes())
+        .map_err(|e| format!("unable to decode {}", e))
+        .and_then(|v| {
+            str::from_utf8(&v)
+                .map_err(|e| format!("unable to decode: {}", e))
+                .map(|s| s.to_owned())
+        })
+}
+
 gtmpl_fn!(
 #[doc = r#"Base 64 encode a string."#]
 fn base64encode(s: String) -> Result<String, String> {
@@ -18,17 +44,23 @@ fn base64encode(s: String) -> Result<String, String> {
 }
 );
 
+gtmpl_fn!(
+#[doc = r#"Base 64 encode a string, wrapping lines at 76 characters with CRLF as
+required for MIME (RFC 2045)."#]
+fn base64encode_mime(s: String) -> Result<String, String> {
+    let enc = BASE64.encode(&s.into_bytes());
+    let lines: Vec<_> = enc.as_bytes()
+        .chunks(76)
+        .map(|l| str::from_utf8(l).unwrap())
+        .collect();
+    Ok(lines.join("\r\n"))
+}
+);
+
 gtmpl_fn!(
 #[doc = r#"Base 64 decode a string."#]
 fn base64decode(s: String) -> Result<String, String> {
-    BASE64
-        .decode(&s.into_bytes())
-        .map_err(|e| format!("unable to decode {}", e))
-        .and_then(|v| {
-            str::from_utf8(&v)
-                .map_err(|e| format!("unable to decode: {}", e))
-                .map(|s| s.to_owned())
-        })
+    decode(&BASE64, &s)
 }
 );
 
@@ -42,24 +74,35 @@ fn base32encode(s: String) -> Result<String, String> {
 gtmpl_fn!(
 #[doc = r#"Base 32 decode a string."#]
 fn base32decode(s: String) -> Result<String, String> {
-    BASE32
-        .decode(&s.into_bytes())
-        .map_err(|e| format!("unable to decode {}", e))
-        .and_then(|v| {
-            str::from_utf8(&v)
-                .map_err(|e| format!("unable to decode: {}", e))
-                .map(|s| s.to_owned())
-        })
+    decode(&BASE32, &s)
 }
 );
 
 gtmpl_fn!(
-#[doc = r#"Truncate a string with ellipses. `abbrev 5 "hello world"` yields "he...""#]
+#[doc = r#"Base 32 encode a string with Crockford's alphabet, which avoids the easily
+confused letters I, L, O and U."#]
+fn base32crockford_encode(s: String) -> Result<String, String> {
+    Ok(BASE32_CROCKFORD.encode(&s.into_bytes()))
+}
+);
+
+gtmpl_fn!(
+#[doc = r#"Decode a string encoded with Crockford's base 32 alphabet. Case, hyphens and
+the look-alikes I, L (for 1) and O (for 0) are accepted."#]
+fn base32crockford_decode(s: String) -> Result<String, String> {
+    decode(&BASE32_CROCKFORD, &s)
+}
+);
+
+gtmpl_fn!(
+#[doc = r#"Truncate a string with ellipses. `abbrev 5 "hello world"` yields "he..."
+The width counts characters, not bytes."#]
 fn abbrev(width: i64, s: String) -> Result<String, String> {
-    if width < 4 || s.len() < width as usize {
+    if width < 4 || s.chars().count() < width as usize {
         Ok(s)
     } else {
-        Ok(format!("{}...", &s[..(width as usize - 3)]))
+        let head: String = s.chars().take(width as usize - 3).collect();
+        Ok(format!("{}...", head))
     }
 }
 );
@@ -86,11 +129,9 @@ fn abbrevboth(left: i64, right: i64, s: String) -> Result<String, String> {
 gtmpl_fn!(
 #[doc = r#"Given a multi-word string, return the initials. `initials "Matt Butcher"` returns "MB""#]
 fn initials(s: String) -> Result<String, String> {
-    Ok(
-        s.split_whitespace()
-            .map(|w| (&w[0..1]).to_owned())
-            .collect(),
-    )
+    // split_whitespace never yields empty words, so runs of whitespace are
+    // simply skipped.
+    Ok(s.split_whitespace().filter_map(|w| w.chars().next()).collect())
 }
 );
 
@@ -142,6 +183,50 @@ fn untitle(s: String) -> Result<String, String> {
 }
 );
 
+fn recase_words<B, F>(s: &str, is_boundary: B, recase: F) -> String
+where
+    B: Fn(char) -> bool,
+    F: Fn(char) -> String,
+{
+    let mut boundary = true;
+    s.chars()
+        .map(|c| if is_boundary(c) {
+            boundary = true;
+            c.to_string()
+        } else if boundary {
+            boundary = false;
+            recase(c)
+        } else {
+            c.to_string()
+        })
+        .collect()
+}
+
+gtmpl_fn!(
+#[doc = r#"Title case words separated by whitespace or any of the given characters:
+`titleWords "-" "mary-jane watson"` yields "Mary-Jane Watson". Only the first
+letter of each word is changed."#]
+fn title_words(seps: String, s: String) -> Result<String, String> {
+    Ok(recase_words(
+        &s,
+        |c| c.is_whitespace() || seps.contains(c),
+        |c| c.to_uppercase().to_string(),
+    ))
+}
+);
+
+gtmpl_fn!(
+#[doc = r#"Remove title casing from words separated by whitespace or any of the given
+characters: `untitleWords "-" "Mary-Jane Watson"` yields "mary-jane watson"."#]
+fn untitle_words(seps: String, s: String) -> Result<String, String> {
+    Ok(recase_words(
+        &s,
+        |c| c.is_whitespace() || seps.contains(c),
+        |c| c.to_lowercase().to_string(),
+    ))
+}
+);
+
 gtmpl_fn!(
 fn replace(old: String, new: String, s: String) -> Result<String, String> {
     Ok(s.replace(&old, &new))
@@ -167,24 +252,25 @@ fn trunc(len: i64, s: String) -> Result<String, String> {
 
 /// Golang's strings.Join, but as `join SEP SLICE`
 pub fn join(args: &[Arc<Any>]) -> Result<Arc<Any>, String> {
-    if args.len() != 2 {
-        return Err(String::from("two arguments required"));
-    }
-    let arg0 = args[0].downcast_ref::<Value>().ok_or_else(|| {
-        "unable to downcast".to_owned()
-    })?;
-    let sep: String = from_value(arg0).ok_or_else(
-        || "unable to convert from Value".to_owned(),
-    )?;
-    let arg1 = args[1].downcast_ref::<Value>().ok_or_else(|| {
-        "unable to downcast".to_owned()
+    utils::arg_count("join", 2, args)?;
+    let arg0 = utils::downcast_arg("join", args, 0)?;
+    let sep: String = from_value(arg0).ok_or_else(|| {
+        utils::type_error("join", 1, "string", arg0)
     })?;
+    let arg1 = utils::downcast_arg("join", args, 1)?;
     if let Value::Array(ref list) = *arg1 {
-        Ok(Arc::new(Value::from(
-            itertools::join(list.iter().map(|v| v.to_string()), &sep),
-        )))
+        // Write straight into one buffer instead of stringifying each element
+        // first, which matters for huge lists.
+        let mut joined = String::with_capacity(list.len() * (sep.len() + 1));
+        for (i, v) in list.iter().enumerate() {
+            if i > 0 {
+                joined.push_str(&sep);
+            }
+            write!(joined, "{}", v).map_err(|e| format!("join: {}", e))?;
+        }
+        Ok(Arc::new(Value::from(joined)))
     } else {
-        return Err(String::from("second argument must be of type Array"));
+        return Err(utils::type_error("join", 2, "list", arg1));
     }
 }
 
@@ -203,15 +289,34 @@ fn split(sep: String, orig: String) -> Result<HashMap<String, String>, String> {
 );
 
 gtmpl_fn!(
-#[doc = r#"Given string, start, and length, return a substr."#]
-fn substring(start: i64, len: i64, s: String) -> Result<String, String> {
+#[doc = r#"Golang's strings.Split, but as `splitList SEP STRING`, returning a list.
+Exactly like Go, an empty string yields a list containing one empty string and an
+empty separator splits after each character."#]
+fn split_list(sep: String, orig: String) -> Result<Vec<String>, String> {
+    if sep.is_empty() {
+        return Ok(orig.chars().map(|c| c.to_string()).collect());
+    }
+    Ok(orig.split(&sep).map(|s| s.to_owned()).collect())
+}
+);
+
+gtmpl_fn!(
+#[doc = r#"Substring from character index `start` up to (excluding) `end`, like Go's
+`s[start:end]`: `substring 1 4 "foobar"` yields "oob". A negative `start` counts
+as 0, a negative `end` or one past the string as its end. If `start` is not before
+`end` the result is empty."#]
+fn substring(start: i64, end: i64, s: String) -> Result<String, String> {
+    let len = s.chars().count();
     let start = if start < 0 { 0 } else { start as usize };
-    let len = if len < 0 { s.len() } else { len as usize };
-    if start > len || start > s.len() || len > s.len() {
-        Ok(s)
+    let end = if end < 0 || end as usize > len {
+        len
     } else {
-        Ok(s[start..len].to_string())
+        end as usize
+    };
+    if start >= end {
+        return Ok(String::new());
     }
+    Ok(s.chars().skip(start).take(end - start).collect())
 }
 );
 
@@ -235,14 +340,14 @@ gtmpl_fn!(
 #[doc=r#"Golang's strings.TrimSuffix, but with the argument order reversed:
 `trimSuffix "-" "ends-with-"`"#]
 fn trim_suffix(substr: String, s: String) -> Result<String, String> {
-    Ok(s.trim_right_matches(&substr).to_owned())
+    Ok(s.strip_suffix(substr.as_str()).unwrap_or(&s).to_owned())
 }
 );
 
 gtmpl_fn!(
 #[doc=r#"Golang's strings.TrimPrefix, but with the argument order reversed `trimPrefix "$" "$5"`"#]
 fn trim_prefix(substr: String, s: String) -> Result<String, String> {
-    Ok(s.trim_left_matches(&substr).to_owned())
+    Ok(s.strip_prefix(substr.as_str()).unwrap_or(&s).to_owned())
 }
 );
 
@@ -267,6 +372,501 @@ fn has_prefix(substr: String, s: String) -> Result<bool, String> {
 }
 );
 
+gtmpl_fn!(
+#[doc=r#"Shell style wildcard match of a whole string: `matchesGlob "*.txt" "a.txt"`.
+`*` matches any sequence of characters and `?` a single character."#]
+fn matches_glob(pattern: String, s: String) -> Result<bool, String> {
+    Glob::new(&pattern)
+        .map(|g| g.compile_matcher().is_match(&s))
+        .map_err(|e| format!("invalid glob {}: {}", pattern, e))
+}
+);
+
+gtmpl_fn!(
+#[doc=r#"Remove one matching pair of surrounding single or double quotes:
+`trimQuotes "\"foo\""` yields "foo". Unquoted strings are returned unchanged."#]
+fn trim_quotes(s: String) -> Result<String, String> {
+    let quoted = s.len() >= 2 &&
+        (s.starts_with('"') && s.ends_with('"') || s.starts_with('\'') && s.ends_with('\''));
+    if quoted {
+        Ok(s[1..s.len() - 1].to_owned())
+    } else {
+        Ok(s)
+    }
+}
+);
+
+gtmpl_fn!(
+#[doc=r#"Number of whitespace separated words in a string."#]
+fn word_count(s: String) -> Result<i64, String> {
+    Ok(s.split_whitespace().count() as i64)
+}
+);
+
+gtmpl_fn!(
+#[doc=r#"Number of characters (unicode scalar values, not bytes) in a string."#]
+fn char_count(s: String) -> Result<i64, String> {
+    Ok(s.chars().count() as i64)
+}
+);
+
+gtmpl_fn!(
+#[doc=r#"Reverse a string by characters: `reverse "abc"` yields "cba"."#]
+fn reverse_str(s: String) -> Result<String, String> {
+    Ok(s.chars().rev().collect())
+}
+);
+
+fn recase_first<F: Fn(char) -> String>(s: &str, recase: F) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(c) => recase(c) + chars.as_str(),
+        None => String::new(),
+    }
+}
+
+gtmpl_fn!(
+#[doc=r#"Uppercase the first character of a string: `capitalize "hello world"` yields
+"Hello world"."#]
+fn capitalize(s: String) -> Result<String, String> {
+    Ok(recase_first(&s, |c| c.to_uppercase().to_string()))
+}
+);
+
+gtmpl_fn!(
+#[doc=r#"Lowercase the first character of a string: `uncapitalize "Hello World"` yields
+"hello World"."#]
+fn uncapitalize(s: String) -> Result<String, String> {
+    Ok(recase_first(&s, |c| c.to_lowercase().to_string()))
+}
+);
+
+gtmpl_fn!(
+#[doc=r#"Whether a string is empty or consists of whitespace only."#]
+fn is_blank(s: String) -> Result<bool, String> {
+    Ok(s.trim().is_empty())
+}
+);
+
+/// First argument that is not blank (see `isBlank`), as `coalesceStr A B ...`.
+/// Nil values are skipped, if all arguments are blank the result is "".
+pub fn coalesce_str(args: &[Arc<Any>]) -> Result<Arc<Any>, String> {
+    for i in 0..args.len() {
+        match *utils::downcast_arg("coalesceStr", args, i)? {
+            Value::String(ref s) if !s.trim().is_empty() => {
+                return Ok(Arc::new(Value::from(s.clone())));
+            }
+            Value::String(_) | Value::Nil | Value::NoValue => {}
+            ref v => return Err(utils::type_error("coalesceStr", i + 1, "string", v)),
+        }
+    }
+    Ok(Arc::new(Value::from("")))
+}
+
+gtmpl_fn!(
+#[doc=r#"Trim a string, reporting whether anything was removed. Yields a map with the
+trimmed string as `trimmed` and a bool `changed`."#]
+fn trim_report(s: String) -> Result<HashMap<String, Value>, String> {
+    let trimmed = s.trim();
+    let mut m = HashMap::new();
+    m.insert("changed".to_owned(), Value::from(trimmed.len() != s.len()));
+    m.insert("trimmed".to_owned(), Value::from(trimmed));
+    Ok(m)
+}
+);
+
+gtmpl_fn!(
+#[doc=r#"Python style substring by characters, as `substrNeg START END STRING`.
+Negative indexes count from the end of the string and an `END` of 0 means the end
+of the string: `substrNeg -3 0 "foobar"` yields "bar". Indexes are clamped to the
+string."#]
+fn substr_neg(start: i64, end: i64, s: String) -> Result<String, String> {
+    let len = s.chars().count() as i64;
+    let index = |i: i64| if i < 0 { (len + i).max(0) } else { i.min(len) };
+    let start = index(start);
+    let end = if end == 0 { len } else { index(end) };
+    if start >= end {
+        return Ok(String::new());
+    }
+    Ok(
+        s.chars()
+            .skip(start as usize)
+            .take((end - start) as usize)
+            .collect(),
+    )
+}
+);
+
+/// Largest string, in bytes, built by repeating a string, e.g. by `repeatSep`.
+const MAX_REPEAT_LEN: usize = 1 << 20;
+
+/// Fail unless `count` pieces of `len` bytes fit into `MAX_REPEAT_LEN`.
+fn check_repeat_len(name: &str, count: usize, len: usize) -> Result<(), String> {
+    match count.checked_mul(len) {
+        Some(total) if total <= MAX_REPEAT_LEN => Ok(()),
+        _ => Err(format!("{}: result exceeds {} bytes", name, MAX_REPEAT_LEN)),
+    }
+}
+
+gtmpl_fn!(
+#[doc=r#"Repeat a string, joined by a separator: `repeatSep 3 ", " "x"` yields
+"x, x, x". A count of 0 or less yields an empty string, a result longer than
+1 MiB is an error."#]
+fn repeat_sep(count: i64, sep: String, s: String) -> Result<String, String> {
+    if count <= 0 || (s.is_empty() && sep.is_empty()) {
+        return Ok(String::new());
+    }
+    check_repeat_len("repeatSep", count as usize, s.len() + sep.len())?;
+    Ok(vec![s; count as usize].join(&sep))
+}
+);
+
+gtmpl_fn!(
+#[doc=r#"Character index of the last occurrence of a substring, or -1 if it isn't
+found: `lastIndexOf "o" "foo"` yields 2."#]
+fn last_index_of(substr: String, s: String) -> Result<i64, String> {
+    Ok(match s.rfind(&substr) {
+        Some(i) => s[..i].chars().count() as i64,
+        None => -1,
+    })
+}
+);
+
+gtmpl_fn!(
+#[doc=r#"Split a string into lines, accepting both `\n` and `\r\n` line endings. The
+line endings are removed and a final line ending doesn't yield an empty line."#]
+fn split_lines(s: String) -> Result<Vec<String>, String> {
+    Ok(s.lines().map(|l| l.to_owned()).collect())
+}
+);
+
+fn wrap_line(width: usize, sep: &str, line: &str) -> String {
+    if line.chars().count() <= width {
+        return line.to_owned();
+    }
+    let body = line.trim_start();
+    let indent = &line[..line.len() - body.len()];
+    let indent_len = indent.chars().count();
+    let mut out = indent.to_owned();
+    let mut len = indent_len;
+    for word in body.split_whitespace() {
+        let word_len = word.chars().count();
+        if len > indent_len && len + 1 + word_len > width {
+            out.push_str(sep);
+            out.push_str(indent);
+            len = indent_len;
+        } else if len > indent_len {
+            out.push(' ');
+            len += 1;
+        }
+        out.push_str(word);
+        len += word_len;
+    }
+    out
+}
+
+fn wrap_with_sep(width: i64, sep: &str, s: &str) -> Result<String, String> {
+    if width < 1 {
+        return Err(format!("invalid width {}", width));
+    }
+    let lines: Vec<_> = s.split('\n')
+        .map(|l| wrap_line(width as usize, sep, l))
+        .collect();
+    Ok(lines.join("\n"))
+}
+
+gtmpl_fn!(
+#[doc=r#"Wrap text at the given width, as `wrap WIDTH STRING`. Existing newlines are
+kept as hard breaks and each paragraph is wrapped on its own. Lines that fit are
+left as they are, and wrapped lines keep their leading indentation on every
+line. Words longer than the width are not split."#]
+fn wrap(width: i64, s: String) -> Result<String, String> {
+    wrap_with_sep(width, "\n", &s)
+}
+);
+
+gtmpl_fn!(
+#[doc=r#"Like `wrap`, but breaking lines with the given string instead of a newline:
+`wrapWith 5 "\t" "Hello World"` yields "Hello\tWorld". Existing newlines are kept."#]
+fn wrap_with(width: i64, sep: String, s: String) -> Result<String, String> {
+    wrap_with_sep(width, &sep, &s)
+}
+);
+
+fn dedent_str(s: &str) -> String {
+    let prefix = s.split('\n')
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| &l[..l.len() - l.trim_start().len()])
+        .fold(None, |common: Option<&str>, indent| match common {
+            None => Some(indent),
+            Some(common) => {
+                let len = common
+                    .char_indices()
+                    .zip(indent.chars())
+                    .take_while(|&((_, a), b)| a == b)
+                    .last()
+                    .map(|((i, c), _)| i + c.len_utf8())
+                    .unwrap_or(0);
+                Some(&common[..len])
+            }
+        })
+        .unwrap_or("");
+    let lines: Vec<_> = s.split('\n')
+        .map(|l| if l.trim().is_empty() { "" } else { &l[prefix.len()..] })
+        .collect();
+    lines.join("\n")
+}
+
+gtmpl_fn!(
+#[doc=r#"Remove the longest common leading whitespace from every line. Lines containing
+only whitespace are ignored for the common prefix and emptied."#]
+fn dedent(s: String) -> Result<String, String> {
+    Ok(dedent_str(&s))
+}
+);
+
+/// Prefix every line of `s` with `count` times `unit`, the one rule shared by
+/// all indentation functions. Empty lines, including the one after a trailing
+/// newline, are left empty rather than getting trailing whitespace. A negative
+/// count, or one making the prefix longer than `MAX_REPEAT_LEN`, is an error.
+fn indent_with(unit: &str, count: i64, s: &str) -> Result<String, String> {
+    if count < 0 || (count as u64).saturating_mul(unit.len() as u64) > MAX_REPEAT_LEN as u64 {
+        return Err(format!("invalid indentation {}", count));
+    }
+    let pad = unit.repeat(count as usize);
+    let lines: Vec<_> = s.split('\n')
+        .map(|l| if l.is_empty() {
+            String::new()
+        } else {
+            format!("{}{}", pad, l)
+        })
+        .collect();
+    Ok(lines.join("\n"))
+}
+
+gtmpl_fn!(
+#[doc=r#"Replace the common indentation of a block (see `dedent`) with the given
+number of spaces: `reindent 4 "  foo\n    bar"` yields "    foo\n      bar"."#]
+fn reindent(spaces: i64, s: String) -> Result<String, String> {
+    indent_with(" ", spaces, &dedent_str(&s))
+}
+);
+
+gtmpl_fn!(
+#[doc=r#"Prefix every line with the given number of spaces: `indent 2 "a\nb"` yields
+"  a\n  b". Empty lines stay empty, so a trailing newline is kept as is."#]
+fn indent(count: i64, s: String) -> Result<String, String> {
+    indent_with(" ", count, &s)
+}
+);
+
+gtmpl_fn!(
+#[doc=r#"Like `indent`, but starting with a newline: `nindent 2 "a\nb"` yields
+"\n  a\n  b"."#]
+fn nindent(count: i64, s: String) -> Result<String, String> {
+    indent_with(" ", count, &s).map(|s| format!("\n{}", s))
+}
+);
+
+gtmpl_fn!(
+#[doc=r#"Like Sprig's `indent`, but prefixing every line with the given number of
+tabs instead of spaces."#]
+fn indent_tabs(count: i64, s: String) -> Result<String, String> {
+    indent_with("\t", count, &s)
+}
+);
+
+gtmpl_fn!(
+#[doc=r#"Like `indentTabs`, but starting with a newline, as Sprig's `nindent`."#]
+fn nindent_tabs(count: i64, s: String) -> Result<String, String> {
+    indent_with("\t", count, &s).map(|s| format!("\n{}", s))
+}
+);
+
+/// Split a string into words at whitespace, `-`, `_` and case changes. A run
+/// of capitals is one word, except for the last capital if a lowercase letter
+/// follows ("HTTPServer" is "HTTP" and "Server"). Digits stick to the word
+/// before them.
+fn split_words(s: &str) -> Vec<String> {
+    let chars: Vec<char> = s.chars().collect();
+    let mut words = vec![];
+    let mut word = String::new();
+    for (i, &c) in chars.iter().enumerate() {
+        if c.is_whitespace() || c == '-' || c == '_' {
+            if !word.is_empty() {
+                words.push(word);
+                word = String::new();
+            }
+            continue;
+        }
+        if c.is_uppercase() && !word.is_empty() {
+            let prev = chars[i - 1];
+            let next_lower = chars.get(i + 1).map_or(false, |n| n.is_lowercase());
+            if prev.is_lowercase() || prev.is_numeric() || prev.is_uppercase() && next_lower {
+                words.push(word);
+                word = String::new();
+            }
+        }
+        word.push(c);
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+gtmpl_fn!(
+#[doc=r#"Convert to snake case: `snakecase "HTTPServer"` yields "http_server"."#]
+fn snakecase(s: String) -> Result<String, String> {
+    Ok(split_words(&s).join("_").to_lowercase())
+}
+);
+
+gtmpl_fn!(
+#[doc=r#"Convert to kebab case: `kebabcase "FooBar"` yields "foo-bar"."#]
+fn kebabcase(s: String) -> Result<String, String> {
+    Ok(split_words(&s).join("-").to_lowercase())
+}
+);
+
+gtmpl_fn!(
+#[doc=r#"Convert to (upper) camel case: `camelcase "foo_bar"` yields "FooBar"."#]
+fn camelcase(s: String) -> Result<String, String> {
+    Ok(
+        split_words(&s)
+            .iter()
+            .map(|w| recase_first(&w.to_lowercase(), |c| c.to_uppercase().to_string()))
+            .collect(),
+    )
+}
+);
+
+gtmpl_fn!(
+#[doc=r#"Convert to upper case."#]
+fn upper(s: String) -> Result<String, String> {
+    Ok(s.to_uppercase())
+}
+);
+
+gtmpl_fn!(
+#[doc=r#"Convert to lower case."#]
+fn lower(s: String) -> Result<String, String> {
+    Ok(s.to_lowercase())
+}
+);
+
+/// Go's `isSeparator` from `strings.Title`.
+fn is_title_separator(c: char) -> bool {
+    if c.is_ascii() {
+        !(c.is_ascii_alphanumeric() || c == '_')
+    } else {
+        c.is_whitespace()
+    }
+}
+
+gtmpl_fn!(
+#[doc=r#"Title case, like Go's strings.Title: the first letter of each word is upper
+cased, the rest is left alone. As in Go, words are separated by whitespace and
+by any ASCII character other than letters, digits and `_`, so
+`title "mary-jane o'neil"` yields "Mary-Jane O'Neil"."#]
+fn title(s: String) -> Result<String, String> {
+    Ok(recase_words(&s, is_title_separator, |c| c.to_uppercase().to_string()))
+}
+);
+
+gtmpl_fn!(
+#[doc=r#"Invert the case of every character: `swapcase "Hello"` yields "hELLO"."#]
+fn swapcase(s: String) -> Result<String, String> {
+    Ok(
+        s.chars()
+            .map(|c| if c.is_uppercase() {
+                c.to_lowercase().to_string()
+            } else if c.is_lowercase() {
+                c.to_uppercase().to_string()
+            } else {
+                c.to_string()
+            })
+            .collect(),
+    )
+}
+);
+
+gtmpl_fn!(
+#[doc=r#"Repeat a string: `repeat 3 "ab"` yields "ababab". A negative count, or a
+result longer than 1 MiB, is an error."#]
+fn repeat(count: i64, s: String) -> Result<String, String> {
+    if count < 0 {
+        return Err(format!("negative repeat count {}", count));
+    }
+    check_repeat_len("repeat", count as usize, s.len())?;
+    Ok(s.repeat(count as usize))
+}
+);
+
+gtmpl_fn!(
+#[doc=r#"Remove all whitespace: `nospace "h e l l o"` yields "hello"."#]
+fn nospace(s: String) -> Result<String, String> {
+    Ok(s.chars().filter(|c| !c.is_whitespace()).collect())
+}
+);
+
+/// Double quote a string, escaping like Go's `%q` verb.
+pub fn go_quote(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 || c == '\u{7f}' => {
+                out.push_str(&format!("\\x{:02x}", c as u32))
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn quote_args<F>(name: &str, args: &[Arc<Any>], f: F) -> Result<Arc<Any>, String>
+where
+    F: Fn(&Value) -> String,
+{
+    let mut quoted = vec![];
+    for i in 0..args.len() {
+        match *utils::downcast_arg(name, args, i)? {
+            Value::Nil | Value::NoValue => {}
+            ref v => quoted.push(f(v)),
+        }
+    }
+    Ok(Arc::new(Value::from(quoted.join(" "))))
+}
+
+/// Double quote every argument (escaped like Go's `%q`) and join them with
+/// spaces, as `quote A B ...`. Nil arguments are skipped.
+pub fn quote(args: &[Arc<Any>]) -> Result<Arc<Any>, String> {
+    quote_args("quote", args, |v| go_quote(&v.to_string()))
+}
+
+/// Single quote every argument (without escaping) and join them with spaces,
+/// as `squote A B ...`. Nil arguments are skipped.
+pub fn squote(args: &[Arc<Any>]) -> Result<Arc<Any>, String> {
+    quote_args("squote", args, |v| format!("'{}'", v))
+}
+
+/// Join all arguments with spaces, as `cat A B ...`: `cat "foo" 1 true` yields
+/// "foo 1 true".
+pub fn cat(args: &[Arc<Any>]) -> Result<Arc<Any>, String> {
+    let mut parts = Vec::with_capacity(args.len());
+    for i in 0..args.len() {
+        parts.push(utils::downcast_arg("cat", args, i)?.to_string());
+    }
+    Ok(Arc::new(Value::from(parts.join(" "))))
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -274,36 +874,6 @@ mod test {
     use std::sync::Arc;
     use gtmpl_value::Value;
 
-    macro_rules! varc(
-        ($x:expr) => { { let v: Arc<Any> = Arc::new(Value::from($x)); v } }
-    );
-
-    macro_rules! vvarc(
-        ($($x:expr),*) => { { let v: Vec<Arc<Any>> = vec![$(varc!($x)),*]; v } }
-    );
-
-    macro_rules! test_fn_assert(
-        ($func:ident, $args:expr, $typ:ident, $ass:ident) => {
-            let v = $args;
-            let ret = $func(&v).unwrap();
-            let ret_ = ret.downcast_ref::<Value>();
-            if let Some(&Value::$typ(ref x)) = ret_ {
-                return assert!($ass(x));
-            }
-            assert!(false);
-        }
-    );
-
-    macro_rules! test_fn(
-        ($func:ident, $args:expr, $exp:expr) => {
-            let v = $args;
-            let ret = $func(&v).unwrap();
-            let ret_ = ret.downcast_ref::<Value>();
-            let expected = $exp;
-            assert_eq!(ret_, Some(&Value::from(expected)));
-        }
-    );
-
     #[test]
     fn test_base64encode() {
         test_fn!(base64encode, vvarc!("Hello World!"), "SGVsbG8gV29ybGQh");
@@ -335,6 +905,16 @@ mod test {
     #[test]
     fn test_abbrv() {
         test_fn!(abbrev, vvarc!(4, "foobar"), "f...");
+        test_fn!(abbrev, vvarc!(5, "hello world"), "he...");
+        test_fn!(abbrev, vvarc!(3, "hello world"), "hello world");
+        test_fn!(abbrev, vvarc!(20, "hello world"), "hello world");
+    }
+
+    #[test]
+    fn test_abbrv_multibyte() {
+        test_fn!(abbrev, vvarc!(5, "héllo world"), "hé...");
+        test_fn!(abbrev, vvarc!(6, "😀😀😀😀😀😀😀"), "😀😀😀...");
+        test_fn!(abbrev, vvarc!(8, "héllo"), "héllo");
     }
 
     #[test]
@@ -350,6 +930,9 @@ mod test {
         test_fn!(initials, vvarc!(""), "");
         test_fn!(initials, vvarc!(" "), "");
         test_fn!(initials, vvarc!("Foo Bar"), "FB");
+        test_fn!(initials, vvarc!("  Foo   Bar "), "FB");
+        test_fn!(initials, vvarc!("Éric Müller"), "ÉM");
+        test_fn!(initials, vvarc!("李 四"), "李四");
     }
 
     #[test]
@@ -388,6 +971,19 @@ mod test {
         test_fn!(untitle, vvarc!("  F  B  "), "  f  b  ");
     }
 
+    #[test]
+    fn test_title_words() {
+        test_fn!(title_words, vvarc!("-", "mary-jane watson"), "Mary-Jane Watson");
+        test_fn!(title_words, vvarc!("", "mary-jane"), "Mary-jane");
+        test_fn!(title_words, vvarc!("-.", "a.b-c"), "A.B-C");
+    }
+
+    #[test]
+    fn test_untitle_words() {
+        test_fn!(untitle_words, vvarc!("-", "Mary-Jane Watson"), "mary-jane watson");
+        test_fn!(untitle_words, vvarc!("", "Mary-Jane"), "mary-Jane");
+    }
+
     #[test]
     fn test_replace() {
         test_fn!(replace,
@@ -409,6 +1005,22 @@ mod test {
     #[test]
     fn test_join() {
         test_fn!(join, vvarc!("_", vec!["hello", "world"]), "hello_world");
+        test_fn!(join, vvarc!("_", Vec::<String>::new()), "");
+    }
+
+    #[test]
+    fn test_join_arity() {
+        let v = vvarc!("_");
+        assert_eq!(join(&v).unwrap_err(), "join: two arguments required, got 1");
+        let v = vvarc!("_", vec!["a"], "b");
+        assert_eq!(join(&v).unwrap_err(), "join: two arguments required, got 3");
+    }
+
+    #[test]
+    fn test_join_large() {
+        let list: Vec<i64> = (0..100_000).collect();
+        let expected = ::itertools::join(list.iter(), ", ");
+        test_fn!(join, vvarc!(", ", list), expected);
     }
 
     #[test]
@@ -423,8 +1035,20 @@ mod test {
     fn test_substring() {
         test_fn!(substring, vvarc!(0, 0, ""), "");
         test_fn!(substring, vvarc!(1, 5, "foobar"), "ooba");
-        test_fn!(substring, vvarc!(3, 2, "foobar"), "foobar");
-        test_fn!(substring, vvarc!(8, 9, "foobar"), "foobar");
+        test_fn!(substring, vvarc!(3, 2, "foobar"), "");
+        test_fn!(substring, vvarc!(8, 9, "foobar"), "");
+        test_fn!(substring, vvarc!(-1, 3, "foobar"), "foo");
+        test_fn!(substring, vvarc!(2, 100, "foobar"), "obar");
+        test_fn!(substring, vvarc!(2, -1, "foobar"), "obar");
+    }
+
+    #[test]
+    fn test_substring_multibyte() {
+        test_fn!(substring, vvarc!(0, 1, "€uro"), "€");
+        test_fn!(substring, vvarc!(1, 3, "héllo"), "él");
+        test_fn!(substring, vvarc!(1, 2, "a😀b"), "😀");
+        test_fn!(substring, vvarc!(2, -1, "你好世界"), "世界");
+        test_fn!(substring, vvarc!(2, 9, "你好世界"), "世界");
     }
 
     #[test]
@@ -455,10 +1079,361 @@ mod test {
     #[test]
     fn test_trim_suffix() {
         test_fn!(trim_suffix, vvarc!("bar", "foobar"), "foo");
+        test_fn!(trim_suffix, vvarc!("a", "banana"), "banan");
+        test_fn!(trim_suffix, vvarc!("x", "banana"), "banana");
     }
 
     #[test]
     fn test_trim_prefix() {
         test_fn!(trim_prefix, vvarc!("foo", "foobar"), "bar");
+        test_fn!(trim_prefix, vvarc!("ab", "ababc"), "abc");
+        test_fn!(trim_prefix, vvarc!("x", "ababc"), "ababc");
+    }
+
+    #[test]
+    fn test_matches_glob() {
+        test_fn!(matches_glob, vvarc!("*.txt", "a.txt"), true);
+        test_fn!(matches_glob, vvarc!("?.txt", "ab.txt"), false);
+        test_fn!(matches_glob, vvarc!("*.txt", "a.txt.bak"), false);
+        test_fn_err!(matches_glob, vvarc!("[a", "a"));
+    }
+
+    #[test]
+    fn test_trim_quotes() {
+        test_fn!(trim_quotes, vvarc!("\"foo\""), "foo");
+        test_fn!(trim_quotes, vvarc!("'foo'"), "foo");
+        test_fn!(trim_quotes, vvarc!("foo"), "foo");
+        test_fn!(trim_quotes, vvarc!("\"foo'"), "\"foo'");
+        test_fn!(trim_quotes, vvarc!("\""), "\"");
+    }
+
+    #[test]
+    fn test_word_count() {
+        test_fn!(word_count, vvarc!(""), 0i64);
+        test_fn!(word_count, vvarc!("  foo bar\tbaz\n"), 3i64);
+    }
+
+    #[test]
+    fn test_char_count() {
+        test_fn!(char_count, vvarc!("foobar"), 6i64);
+        test_fn!(char_count, vvarc!("grüße"), 5i64);
+    }
+
+    #[test]
+    fn test_reverse_str() {
+        test_fn!(reverse_str, vvarc!("abc"), "cba");
+        test_fn!(reverse_str, vvarc!("grüße"), "eßürg");
+        test_fn!(reverse_str, vvarc!(""), "");
+    }
+
+    #[test]
+    fn test_capitalize() {
+        test_fn!(capitalize, vvarc!("hello world"), "Hello world");
+        test_fn!(capitalize, vvarc!("élan"), "Élan");
+        test_fn!(capitalize, vvarc!(""), "");
+    }
+
+    #[test]
+    fn test_uncapitalize() {
+        test_fn!(uncapitalize, vvarc!("Hello World"), "hello World");
+        test_fn!(uncapitalize, vvarc!("Élan"), "élan");
+    }
+
+    #[test]
+    fn test_base64encode_mime() {
+        test_fn!(base64encode_mime, vvarc!("Hello World!"), "SGVsbG8gV29ybGQh");
+        let check = |x: &String| {
+            let lines: Vec<_> = x.split("\r\n").collect();
+            lines.len() == 4 && lines.iter().all(|l| l.len() <= 76) &&
+                lines.concat() == BASE64.encode(&[b'x'; 200])
+        };
+        let s = String::from_utf8(vec![b'x'; 200]).unwrap();
+        test_fn_assert!(base64encode_mime, vvarc!(s), String, check);
+    }
+
+    #[test]
+    fn test_is_blank() {
+        test_fn!(is_blank, vvarc!(""), true);
+        test_fn!(is_blank, vvarc!("   "), true);
+        test_fn!(is_blank, vvarc!(" \t\n"), true);
+        test_fn!(is_blank, vvarc!(" x "), false);
+    }
+
+    #[test]
+    fn test_coalesce_str() {
+        test_fn!(coalesce_str, vvarc!("", "  ", "x"), "x");
+        test_fn!(coalesce_str, vvarc!(" y ", "x"), " y ");
+        test_fn!(coalesce_str, vvarc!("", " "), "");
+        test_fn!(coalesce_str, vvarc!(), "");
+        test_fn_err!(coalesce_str, vvarc!("", 1));
+    }
+
+    #[test]
+    fn test_trim_report() {
+        let report = |trimmed: &str, changed: bool| {
+            let mut m = HashMap::new();
+            m.insert("trimmed".to_owned(), Value::from(trimmed));
+            m.insert("changed".to_owned(), Value::from(changed));
+            m
+        };
+        test_fn!(trim_report, vvarc!("foo"), report("foo", false));
+        test_fn!(trim_report, vvarc!(" foo\n"), report("foo", true));
+    }
+
+    #[test]
+    fn test_substr_neg() {
+        test_fn!(substr_neg, vvarc!(-3, 0, "foobar"), "bar");
+        test_fn!(substr_neg, vvarc!(1, -1, "foobar"), "ooba");
+        test_fn!(substr_neg, vvarc!(-10, 2, "foobar"), "fo");
+        test_fn!(substr_neg, vvarc!(4, 2, "foobar"), "");
+        test_fn!(substr_neg, vvarc!(-2, 0, "grüße"), "ße");
+    }
+
+    #[test]
+    fn test_repeat_sep() {
+        test_fn!(repeat_sep, vvarc!(3, ", ", "x"), "x, x, x");
+        test_fn!(repeat_sep, vvarc!(1, ", ", "x"), "x");
+        test_fn!(repeat_sep, vvarc!(0, ", ", "x"), "");
+        test_fn!(repeat_sep, vvarc!(-1, ", ", "x"), "");
+        test_fn_err!(repeat_sep, vvarc!(1_000_000_000_000i64, ", ", "x"));
+        test_fn!(repeat_sep, vvarc!(1_000_000_000_000i64, "", ""), "");
+    }
+
+    #[test]
+    fn test_split_list() {
+        test_fn!(split_list, vvarc!(",", "a,b,,c"), vec!["a", "b", "", "c"]);
+        test_fn!(split_list, vvarc!(",", ""), vec![""]);
+        test_fn!(split_list, vvarc!("", "ab"), vec!["a", "b"]);
+    }
+
+    #[test]
+    fn test_last_index_of() {
+        test_fn!(last_index_of, vvarc!("ab", "abcabc"), 3i64);
+        test_fn!(last_index_of, vvarc!("ß", "grüße"), 3i64);
+        test_fn!(last_index_of, vvarc!("x", "abc"), -1i64);
+    }
+
+    #[test]
+    fn test_split_lines() {
+        test_fn!(split_lines, vvarc!("a\r\nb\nc\r\n"), vec!["a", "b", "c"]);
+        test_fn!(split_lines, vvarc!("a\n\nb"), vec!["a", "", "b"]);
+        test_fn!(split_lines, vvarc!(""), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_wrap() {
+        test_fn!(
+            wrap,
+            vvarc!(10, "one two three four\nfive six seven"),
+            "one two\nthree four\nfive six\nseven"
+        );
+        test_fn!(wrap, vvarc!(3, "a verylongword b"), "a\nverylongword\nb");
+        test_fn!(wrap, vvarc!(10, "a\n\nb"), "a\n\nb");
+        test_fn!(wrap, vvarc!(80, "  - item"), "  - item");
+        test_fn!(wrap, vvarc!(80, "a  b"), "a  b");
+        test_fn!(
+            wrap,
+            vvarc!(12, "list:\n  - one two three"),
+            "list:\n  - one two\n  three"
+        );
+        test_fn_err!(wrap, vvarc!(0, "a"));
+    }
+
+    #[test]
+    fn test_dedent() {
+        test_fn!(
+            dedent,
+            vvarc!("    foo:\n      bar: 1\n\n    baz: 2\n"),
+            "foo:\n  bar: 1\n\nbaz: 2\n"
+        );
+        test_fn!(dedent, vvarc!("  a\n\tb"), "  a\n\tb");
+        test_fn!(dedent, vvarc!("foo"), "foo");
+    }
+
+    #[test]
+    fn test_reindent() {
+        test_fn!(
+            reindent,
+            vvarc!(4, "  foo:\n    bar: 1\n\n  baz: 2"),
+            "    foo:\n      bar: 1\n\n    baz: 2"
+        );
+        test_fn!(reindent, vvarc!(0, "  foo"), "foo");
+        test_fn_err!(reindent, vvarc!(-1, "foo"));
+        test_fn_err!(reindent, vvarc!(9223372036854775807i64, "foo"));
+    }
+
+    #[test]
+    fn test_arity() {
+        let v = vvarc!(5);
+        assert_eq!(abbrev(&v).unwrap_err(), "abbrev: two arguments required, got 1");
+        let v = vvarc!("foo", "bar");
+        assert_eq!(trim(&v).unwrap_err(), "trim: one argument required, got 2");
+        let v = vvarc!();
+        assert_eq!(replace(&v).unwrap_err(), "replace: three arguments required, got 0");
+    }
+
+    #[test]
+    fn test_type_error() {
+        let v = vvarc!(42);
+        assert_eq!(trim(&v).unwrap_err(), "trim: argument 1 expected string, got number");
+        let v = vvarc!("5", "foobar");
+        assert_eq!(
+            abbrev(&v).unwrap_err(),
+            "abbrev: argument 1 expected integer, got string"
+        );
+    }
+
+    #[test]
+    fn test_indent_tabs() {
+        test_fn!(indent_tabs, vvarc!(1, "foo\nbar"), "\tfoo\n\tbar");
+        test_fn!(indent_tabs, vvarc!(2, "foo"), "\t\tfoo");
+        test_fn!(indent_tabs, vvarc!(0, "foo"), "foo");
+        test_fn!(indent_tabs, vvarc!(1, "foo\n\nbar"), "\tfoo\n\n\tbar");
+        test_fn_err!(indent_tabs, vvarc!(-1, "foo"));
+        test_fn_err!(indent_tabs, vvarc!(9223372036854775807i64, "foo"));
+    }
+
+    #[test]
+    fn test_nindent_tabs() {
+        test_fn!(nindent_tabs, vvarc!(2, "foo\nbar"), "\n\t\tfoo\n\t\tbar");
+    }
+
+    #[test]
+    fn test_base32crockford() {
+        test_fn!(base32crockford_encode, vvarc!("Hello World!"), "91JPRV3F41BPYWKCCGGG");
+        test_fn!(base32crockford_decode, vvarc!("91JPRV3F41BPYWKCCGGG"), "Hello World!");
+        test_fn!(base32crockford_decode, vvarc!("91jprv3f-41bpywkccggg"), "Hello World!");
+        let check = |x: &String| !x.contains(|c: char| "ILOU".contains(c));
+        test_fn_assert!(
+            base32crockford_encode,
+            vvarc!("The quick brown fox jumps over the lazy dog"),
+            String,
+            check
+        );
+    }
+
+    #[test]
+    fn test_snakecase() {
+        test_fn!(snakecase, vvarc!("CamelCase"), "camel_case");
+        test_fn!(snakecase, vvarc!("HTTPServer"), "http_server");
+        test_fn!(snakecase, vvarc!("foo-bar baz"), "foo_bar_baz");
+        test_fn!(snakecase, vvarc!("version2Update"), "version2_update");
+        test_fn!(snakecase, vvarc!("already_snake"), "already_snake");
+        test_fn!(snakecase, vvarc!(""), "");
+    }
+
+    #[test]
+    fn test_kebabcase() {
+        test_fn!(kebabcase, vvarc!("FooBar"), "foo-bar");
+        test_fn!(kebabcase, vvarc!("getHTTPResponse"), "get-http-response");
+        test_fn!(kebabcase, vvarc!("foo_bar baz"), "foo-bar-baz");
+    }
+
+    #[test]
+    fn test_camelcase() {
+        test_fn!(camelcase, vvarc!("foo_bar"), "FooBar");
+        test_fn!(camelcase, vvarc!("foo-bar baz"), "FooBarBaz");
+        test_fn!(camelcase, vvarc!("HTTPServer"), "HttpServer");
+        test_fn!(camelcase, vvarc!("__foo__bar2"), "FooBar2");
+    }
+
+    #[test]
+    fn test_upper() {
+        test_fn!(upper, vvarc!("foo bär"), "FOO BÄR");
+        test_fn!(upper, vvarc!(""), "");
+    }
+
+    #[test]
+    fn test_lower() {
+        test_fn!(lower, vvarc!("FOO BÄR"), "foo bär");
+        test_fn!(lower, vvarc!(""), "");
+    }
+
+    #[test]
+    fn test_title() {
+        test_fn!(title, vvarc!("hello world"), "Hello World");
+        test_fn!(title, vvarc!("élan vital"), "Élan Vital");
+        test_fn!(title, vvarc!("hELLO"), "HELLO");
+        test_fn!(title, vvarc!("mary-jane o'neil"), "Mary-Jane O'Neil");
+        test_fn!(title, vvarc!("snake_case a.b"), "Snake_case A.B");
+        test_fn!(title, vvarc!(""), "");
+    }
+
+    #[test]
+    fn test_swapcase() {
+        test_fn!(swapcase, vvarc!("Hello World 1"), "hELLO wORLD 1");
+        test_fn!(swapcase, vvarc!("Éa"), "éA");
+        test_fn!(swapcase, vvarc!(""), "");
+    }
+
+    #[test]
+    fn test_repeat() {
+        test_fn!(repeat, vvarc!(3, "ab"), "ababab");
+        test_fn!(repeat, vvarc!(0, "x"), "");
+        test_fn!(repeat, vvarc!(10_000, "x"), "x".repeat(10_000));
+        test_fn_err!(repeat, vvarc!(-1, "x"));
+        test_fn!(repeat, vvarc!(1 << 20, "x"), "x".repeat(1 << 20));
+        test_fn_err!(repeat, vvarc!((1 << 20) + 1, "x"));
+        test_fn_err!(repeat, vvarc!(1_000_000_000_000i64, "ab"));
+    }
+
+    #[test]
+    fn test_nospace() {
+        test_fn!(nospace, vvarc!("h e l l o"), "hello");
+        test_fn!(nospace, vvarc!(" a\tb\nc\r\n"), "abc");
+    }
+
+    #[test]
+    fn test_wrap_with() {
+        test_fn!(wrap_with, vvarc!(5, "\t", "Hello World"), "Hello\tWorld");
+        test_fn!(
+            wrap_with,
+            vvarc!(10, "<br>", "the quick brown fox\njumps over"),
+            "the quick<br>brown fox\njumps over"
+        );
+        test_fn!(wrap_with, vvarc!(4, "|", "a extraordinary b"), "a|extraordinary|b");
+    }
+
+    #[test]
+    fn test_quote() {
+        test_fn!(quote, vvarc!("foo"), "\"foo\"");
+        test_fn!(quote, vvarc!("say \"hi\"\n"), "\"say \\\"hi\\\"\\n\"");
+        test_fn!(quote, vvarc!(""), "\"\"");
+        test_fn!(quote, vvarc!("a", 1, true), "\"a\" \"1\" \"true\"");
+        test_fn!(quote, vvarc!("grüße"), "\"grüße\"");
+    }
+
+    #[test]
+    fn test_squote() {
+        test_fn!(squote, vvarc!("foo"), "'foo'");
+        test_fn!(squote, vvarc!("it's"), "'it's'");
+        test_fn!(squote, vvarc!(""), "''");
+        test_fn!(squote, vvarc!("a", 1), "'a' '1'");
+    }
+
+    #[test]
+    fn test_indent() {
+        test_fn!(indent, vvarc!(2, "a\nb"), "  a\n  b");
+        test_fn!(indent, vvarc!(2, "a\n\nb\n"), "  a\n\n  b\n");
+        test_fn!(indent, vvarc!(4, "a"), "    a");
+        test_fn!(indent, vvarc!(2, ""), "");
+        test_fn_err!(indent, vvarc!(-2, "a"));
+        test_fn_err!(indent, vvarc!(9223372036854775807i64, "a"));
+        test_fn_err!(indent, vvarc!(10_000_000_000i64, "a"));
+    }
+
+    #[test]
+    fn test_nindent() {
+        test_fn!(nindent, vvarc!(2, "a\nb"), "\n  a\n  b");
+        test_fn!(nindent, vvarc!(2, "a\n"), "\n  a\n");
+        test_fn!(nindent, vvarc!(2, ""), "\n");
+    }
+
+    #[test]
+    fn test_cat() {
+        test_fn!(cat, vvarc!("foo", 1, true), "foo 1 true");
+        test_fn!(cat, vvarc!("a", 1.5, "b"), "a 1.5 b");
+        test_fn!(cat, vvarc!("foo"), "foo");
+        test_fn!(cat, vvarc!(), "");
     }
 }
@@ -0,0 +1,175 @@
+use std::any::Any;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use gtmpl::{Context, Func, Template};
+use gtmpl_value::{from_value, Value};
+
+use utils;
+use SPRIG;
+
+/// How deep `tpl` and `include` calls may nest before giving up, to stop
+/// templates which render themselves.
+const MAX_TPL_DEPTH: usize = 16;
+
+/// A template set being rendered: the source holding the templates it
+/// `define`s, which `include` can render, and the functions available to it.
+struct Scope {
+    source: String,
+    funcs: Vec<(String, Func)>,
+}
+
+thread_local! {
+    /// The template sets being rendered on this thread, innermost last.
+    static SCOPES: RefCell<Vec<Rc<Scope>>> = RefCell::new(vec![]);
+}
+
+/// Pops the scope entered for a `tpl` or `include` call when it returns,
+/// however it does.
+struct ScopeGuard;
+
+impl ScopeGuard {
+    fn enter(scope: Rc<Scope>) -> Result<ScopeGuard, String> {
+        SCOPES.with(|s| {
+            let mut s = s.borrow_mut();
+            if s.len() >= MAX_TPL_DEPTH {
+                return Err(format!("templates nested more than {} levels deep", MAX_TPL_DEPTH));
+            }
+            s.push(scope);
+            Ok(ScopeGuard)
+        })
+    }
+}
+
+impl Drop for ScopeGuard {
+    fn drop(&mut self) {
+        SCOPES.with(|s| s.borrow_mut().pop());
+    }
+}
+
+fn current_scope() -> Option<Rc<Scope>> {
+    SCOPES.with(|s| s.borrow().last().cloned())
+}
+
+/// Render `text` within `scope`: either its main body or, if `name` is given,
+/// the template of that name it defines.
+fn render(
+    scope: Rc<Scope>,
+    text: &str,
+    name: Option<&str>,
+    context: Value,
+) -> Result<String, String> {
+    let _guard = ScopeGuard::enter(Rc::clone(&scope))?;
+    let mut tmpl = Template::default();
+    tmpl.add_funcs(&scope.funcs);
+    tmpl.parse(text)?;
+    if let Some(name) = name {
+        if !tmpl.tree_set.contains_key(name) {
+            return Err(format!("include: no template named {:?}", name));
+        }
+        tmpl.name = name.to_owned();
+    }
+    tmpl.render(&Context::from(context)?)
+}
+
+/// Render `text` with the functions `funcs`, making the templates it
+/// `define`s available to `include`. See `Builder::render`.
+pub fn render_template(
+    text: &str,
+    context: Value,
+    funcs: &HashMap<String, Func>,
+) -> Result<String, String> {
+    let scope = Scope {
+        source: text.to_owned(),
+        funcs: funcs.iter().map(|(k, f)| (k.clone(), *f)).collect(),
+    };
+    render(Rc::new(scope), text, None, context)
+}
+
+/// Render a template string with the given context, as `tpl TEMPLATE CONTEXT`,
+/// like Helm's `tpl`. The template gets the functions of the template calling
+/// `tpl`, or all sprig functions outside of `Builder::render`, and may
+/// `include` the templates defined by the calling template as well as its own.
+pub fn tpl(args: &[Arc<Any>]) -> Result<Arc<Any>, String> {
+    utils::arg_count("tpl", 2, args)?;
+    let arg0 = utils::downcast_arg("tpl", args, 0)?;
+    let text: String = from_value(arg0).ok_or_else(|| {
+        utils::type_error("tpl", 1, "string", arg0)
+    })?;
+    let context = utils::downcast_arg("tpl", args, 1)?.clone();
+    let scope = match current_scope() {
+        Some(parent) => Scope {
+            source: format!("{}{}", parent.source, text),
+            funcs: parent.funcs.clone(),
+        },
+        None => Scope {
+            source: text.clone(),
+            funcs: SPRIG.iter().map(|(k, f)| (k.clone(), *f)).collect(),
+        },
+    };
+    render(Rc::new(scope), &text, None, context).map(|r| Arc::new(Value::from(r)) as Arc<Any>)
+}
+
+/// Render a template defined in the template being rendered and return the
+/// output, as `include NAME CONTEXT`, like Helm's `include`. Unlike gtmpl's
+/// built in `template` action, the output can be piped into other functions.
+///
+/// gtmpl doesn't pass the executing template to functions, so this only works
+/// in templates rendered with `Builder::render`, which keeps track of it.
+pub fn include(args: &[Arc<Any>]) -> Result<Arc<Any>, String> {
+    utils::arg_count("include", 2, args)?;
+    let arg0 = utils::downcast_arg("include", args, 0)?;
+    let name: String = from_value(arg0).ok_or_else(|| {
+        utils::type_error("include", 1, "string", arg0)
+    })?;
+    let context = utils::downcast_arg("include", args, 1)?.clone();
+    let scope = current_scope().ok_or_else(|| {
+        "include: only available in templates rendered with Builder::render".to_owned()
+    })?;
+    let source = scope.source.clone();
+    render(scope, &source, Some(&name), context).map(|r| Arc::new(Value::from(r)) as Arc<Any>)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::any::Any;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+    use gtmpl_value::Value;
+
+    fn no_scopes() -> bool {
+        SCOPES.with(|s| s.borrow().is_empty())
+    }
+
+    #[test]
+    fn test_tpl() {
+        let mut ctx = HashMap::new();
+        ctx.insert("name".to_owned(), Value::from("World"));
+        ctx.insert("greeting".to_owned(), Value::from("Hello {{ .name }}"));
+        let ctx = Value::Map(ctx);
+        test_fn!(tpl, vvarc!("{{ .name }}", ctx.clone()), "World");
+        test_fn!(tpl, vvarc!("{{ tpl .greeting . }}!", ctx), "Hello World!");
+    }
+
+    #[test]
+    fn test_tpl_recursion_limit() {
+        let mut ctx = HashMap::new();
+        ctx.insert("self".to_owned(), Value::from("{{ tpl .self . }}"));
+        test_fn_err!(tpl, vvarc!("{{ tpl .self . }}", Value::Map(ctx)));
+        assert!(no_scopes());
+    }
+
+    #[test]
+    fn test_include_errors() {
+        let mut funcs = SPRIG.clone();
+        funcs.insert("include".to_owned(), include as Func);
+        let text = r#"{{ define "loop" }}{{ include "loop" . }}{{ end }}{{ include "loop" . }}"#;
+        assert!(render_template(text, Value::Nil, &funcs).is_err());
+        assert!(no_scopes());
+        assert!(render_template(r#"{{ include "missing" . }}"#, Value::Nil, &funcs).is_err());
+        test_fn_err!(include, vvarc!("missing", Value::Nil));
+    }
+}
@@ -0,0 +1,226 @@
+use std::any::Any;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use gtmpl_value::Value;
+use rand::{Rng, SeedableRng, StdRng};
+
+const ALPHA: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+const NUMERIC: &[u8] = b"0123456789";
+
+thread_local! {
+    static RNG: RefCell<StdRng> = RefCell::new(
+        StdRng::new().expect("unable to seed random number generator"),
+    );
+}
+
+/// Reseed the random number generator used by all random functions
+/// (`randChoice`, `shuffleList`, `randInt`, ...) to get reproducible output,
+/// e.g. in tests.
+///
+/// Each thread has its own generator, so this only affects templates
+/// rendered on the calling thread.
+pub fn seed_rng(seed: usize) {
+    RNG.with(|rng| *rng.borrow_mut() = StdRng::from_seed(&[seed][..]));
+}
+
+/// Run `f` with the shared random number generator.
+pub fn with_rng<T, F: FnOnce(&mut StdRng) -> T>(f: F) -> T {
+    RNG.with(|rng| f(&mut *rng.borrow_mut()))
+}
+
+fn random_from(chars: &[u8], len: usize) -> String {
+    with_rng(|rng| {
+        (0..len)
+            .map(|_| *rng.choose(chars).unwrap() as char)
+            .collect()
+    })
+}
+
+/// Random sequence of alphanumeric unicode characters.
+pub fn random_utf8(len: usize) -> String {
+    with_rng(|rng| {
+        rng.gen_iter::<char>()
+            .filter(|c| c.is_alphanumeric())
+            .take(len)
+            .collect()
+    })
+}
+
+/// Random sequence of ASCII letters.
+pub fn random_alpha(len: usize) -> String {
+    random_from(ALPHA, len)
+}
+
+/// Random sequence of printable ASCII characters (symbols included).
+pub fn random_ascii(len: usize) -> String {
+    with_rng(|rng| {
+        (0..len).map(|_| rng.gen_range(32u8, 127u8) as char).collect()
+    })
+}
+
+/// Random sequence of digits.
+pub fn random_numeric(len: usize) -> String {
+    random_from(NUMERIC, len)
+}
+
+/// Check that a function named `name` got exactly `expected` arguments,
+/// failing with e.g. "join: two arguments required, got 1".
+pub fn arg_count(name: &str, expected: usize, args: &[Arc<Any>]) -> Result<(), String> {
+    if args.len() == expected {
+        return Ok(());
+    }
+    let count = match expected {
+        1 => "one argument".to_owned(),
+        2 => "two arguments".to_owned(),
+        3 => "three arguments".to_owned(),
+        n => format!("{} arguments", n),
+    };
+    Err(format!("{}: {} required, got {}", name, count, args.len()))
+}
+
+/// Downcast argument `i` of the function named `name` to a `Value`.
+pub fn downcast_arg<'a>(
+    name: &str,
+    args: &'a [Arc<Any>],
+    i: usize,
+) -> Result<&'a Value, String> {
+    args[i].downcast_ref::<Value>().ok_or_else(|| {
+        format!("{}: unable to downcast argument {}", name, i + 1)
+    })
+}
+
+/// Template facing name of the Rust type `typ` of a function argument.
+pub fn type_name(typ: &str) -> &str {
+    match typ {
+        "String" => "string",
+        "i64" | "u64" => "integer",
+        "f64" => "number",
+        t => t,
+    }
+}
+
+/// Error for argument `argn` (counting from 1) of the function named `name`
+/// not being of the `expected` type, e.g.
+/// "trim: argument 1 expected string, got number".
+pub fn type_error(name: &str, argn: usize, expected: &str, got: &Value) -> String {
+    let got = match *got {
+        Value::NoValue => "no value",
+        Value::Nil => "nil",
+        Value::Bool(_) => "bool",
+        Value::String(_) => "string",
+        Value::Number(_) => "number",
+        Value::Array(_) => "list",
+        Value::Object(_) | Value::Map(_) => "map",
+        Value::Function(_) => "function",
+    };
+    format!("{}: argument {} expected {}, got {}", name, argn, expected, got)
+}
+
+/// Sprig's notion of emptiness, shared by all functions testing values: nil,
+/// `false`, `0`, and empty strings, lists and maps are empty. Functions never
+/// are.
+pub fn is_empty(v: &Value) -> bool {
+    match *v {
+        Value::NoValue | Value::Nil => true,
+        Value::Bool(b) => !b,
+        Value::Number(ref n) => n.as_f64().map(|f| f == 0.0).unwrap_or(false),
+        Value::String(ref s) => s.is_empty(),
+        Value::Array(ref a) => a.is_empty(),
+        Value::Object(ref m) |
+        Value::Map(ref m) => m.is_empty(),
+        Value::Function(_) => false,
+    }
+}
+
+/// Entries of a map, sorted by key.
+///
+/// Maps are stored as `HashMap`s, whose iteration order is arbitrary. Like
+/// Go's templates, every function iterating a map or emitting its entries
+/// (in a list, JSON, ...) goes through this to keep its output deterministic.
+pub fn sorted_entries<V>(m: &HashMap<String, V>) -> Vec<(&String, &V)> {
+    let mut entries: Vec<_> = m.iter().collect();
+    entries.sort_by(|a, b| a.0.cmp(b.0));
+    entries
+}
+
+/// Replacement for gtmpl's `gtmpl_fn!`, defining a template function from a
+/// plain Rust function. On top of converting the arguments, it checks the
+/// argument count and names the function in its errors, e.g.
+/// "abbrev: two arguments required, got 1".
+macro_rules! gtmpl_fn {
+    (
+        $(#[$outer:meta])*
+        fn $name:ident($($arg:ident : $typ:ty),*) -> Result<$otyp:ty, String>
+        { $($body:tt)* }
+    ) => {
+        $(#[$outer])*
+        pub fn $name(
+            args: &[::std::sync::Arc<::std::any::Any>],
+        ) -> Result<::std::sync::Arc<::std::any::Any>, String> {
+            fn inner($($arg: $typ),*) -> Result<$otyp, String> {
+                $($body)*
+            }
+            let expected = <[&str]>::len(&[$(stringify!($arg)),*]);
+            $crate::utils::arg_count(stringify!($name), expected, args)?;
+            #[allow(unused_mut, unused_variables)]
+            let mut values = (0..args.len())
+                .map(|i| $crate::utils::downcast_arg(stringify!($name), args, i))
+                .collect::<Result<Vec<_>, String>>()?
+                .into_iter()
+                .enumerate();
+            $(
+                let (i, v) = values.next().unwrap();
+                let $arg: $typ = ::gtmpl_value::from_value(v).ok_or_else(|| {
+                    let expected = $crate::utils::type_name(stringify!($typ));
+                    $crate::utils::type_error(stringify!($name), i + 1, expected, v)
+                })?;
+            )*
+            let ret = inner($($arg),*)?;
+            Ok(::std::sync::Arc::new(::gtmpl_value::Value::from(ret)))
+        }
+    }
+}
+
+#[cfg(test)]
+macro_rules! varc(
+    ($x:expr) => { { let v: Arc<Any> = Arc::new(Value::from($x)); v } }
+);
+
+#[cfg(test)]
+macro_rules! vvarc(
+    ($($x:expr),*) => { { let v: Vec<Arc<Any>> = vec![$(varc!($x)),*]; v } }
+);
+
+#[cfg(test)]
+macro_rules! test_fn_assert(
+    ($func:ident, $args:expr, $typ:ident, $ass:ident) => {
+        let v = $args;
+        let ret = $func(&v).unwrap();
+        let ret_ = ret.downcast_ref::<Value>();
+        if let Some(&Value::$typ(ref x)) = ret_ {
+            return assert!($ass(x));
+        }
+        assert!(false);
+    }
+);
+
+#[cfg(test)]
+macro_rules! test_fn(
+    ($func:ident, $args:expr, $exp:expr) => {
+        let v = $args;
+        let ret = $func(&v).unwrap();
+        let ret_ = ret.downcast_ref::<Value>();
+        let expected = $exp;
+        assert_eq!(ret_, Some(&Value::from(expected)));
+    }
+);
+
+#[cfg(test)]
+macro_rules! test_fn_err(
+    ($func:ident, $args:expr) => {
+        let v = $args;
+        assert!($func(&v).is_err());
+    }
+);
@@ -0,0 +1,89 @@
+use std::any::Any;
+use std::sync::Arc;
+
+use gtmpl_value::{from_value, Value};
+use serde_json;
+use serde_yaml;
+
+use serde_value;
+use utils;
+
+/// Serialize a value as YAML, as `toYaml VALUE`. Like Helm, the output is in
+/// block style without a leading `---` document marker or a trailing newline,
+/// so it can be piped into `nindent`. Map keys are sorted.
+pub fn to_yaml(args: &[Arc<Any>]) -> Result<Arc<Any>, String> {
+    utils::arg_count("toYaml", 1, args)?;
+    let arg0 = utils::downcast_arg("toYaml", args, 0)?;
+    let yaml = serde_yaml::to_string(&serde_value::to_serde(arg0)).map_err(|e| {
+        format!("toYaml: {}", e)
+    })?;
+    let body = if yaml.starts_with("---\n") || yaml.starts_with("--- ") {
+        &yaml[4..]
+    } else {
+        &yaml[..]
+    };
+    Ok(Arc::new(Value::from(body.trim_end_matches('\n'))))
+}
+
+/// Parse a YAML document, as `fromYaml STRING`. Plain scalars are typed as
+/// YAML resolves them (`true` is a bool, `123` an integer, `1.5` a float),
+/// quoted scalars stay strings. Mappings become maps.
+pub fn from_yaml(args: &[Arc<Any>]) -> Result<Arc<Any>, String> {
+    utils::arg_count("fromYaml", 1, args)?;
+    let arg0 = utils::downcast_arg("fromYaml", args, 0)?;
+    let s: String = from_value(arg0).ok_or_else(|| {
+        utils::type_error("fromYaml", 1, "string", arg0)
+    })?;
+    let yaml: serde_json::Value = serde_yaml::from_str(&s).map_err(|e| {
+        format!("fromYaml: invalid YAML: {}", e)
+    })?;
+    Ok(Arc::new(serde_value::from_serde(&yaml)))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::any::Any;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+    use gtmpl_value::Value;
+
+    #[test]
+    fn test_to_yaml() {
+        let mut resources = HashMap::new();
+        resources.insert("cpu".to_owned(), Value::from("100m"));
+        resources.insert("memory".to_owned(), Value::from("128Mi"));
+        let mut m = HashMap::new();
+        m.insert("resources".to_owned(), Value::Map(resources));
+        m.insert("ports".to_owned(), Value::from(vec![80, 443]));
+        test_fn!(
+            to_yaml,
+            vvarc!(Value::Map(m)),
+            "ports:\n  - 80\n  - 443\nresources:\n  cpu: 100m\n  memory: 128Mi"
+        );
+        test_fn!(to_yaml, vvarc!("foo"), "foo");
+        test_fn!(to_yaml, vvarc!(1), "1");
+    }
+
+    #[test]
+    fn test_from_yaml() {
+        test_fn!(from_yaml, vvarc!("true"), true);
+        test_fn!(from_yaml, vvarc!("123"), 123i64);
+        test_fn!(from_yaml, vvarc!("1.5"), 1.5f64);
+        test_fn!(from_yaml, vvarc!("\"123\""), "123");
+        test_fn!(from_yaml, vvarc!("'true'"), "true");
+        test_fn!(from_yaml, vvarc!("foo"), "foo");
+
+        let mut expected = HashMap::new();
+        expected.insert("enabled".to_owned(), Value::from(true));
+        expected.insert("replicas".to_owned(), Value::from(3));
+        expected.insert("ratio".to_owned(), Value::from(1.5));
+        expected.insert("version".to_owned(), Value::from("1.10"));
+        test_fn!(
+            from_yaml,
+            vvarc!("enabled: true\nreplicas: 3\nratio: 1.5\nversion: \"1.10\"\n"),
+            Value::Map(expected)
+        );
+        test_fn_err!(from_yaml, vvarc!("a: [1"));
+    }
+}
@@ -0,0 +1,45 @@
+extern crate gtmpl_value;
+extern crate sprig;
+
+use std::collections::HashMap;
+
+use gtmpl_value::Value;
+use sprig::Builder;
+
+fn context() -> Value {
+    let mut ctx = HashMap::new();
+    ctx.insert("name".to_owned(), Value::from("World"));
+    Value::Map(ctx)
+}
+
+#[test]
+fn include_defined_template() {
+    let text = r#"{{ define "greeting" }}Hello {{ .name }}{{ end }}
+{{- define "page" }}<h1>{{ include "greeting" . | upper }}</h1>{{ end }}
+{{- include "page" . }}"#;
+    let out = Builder::new().with_all().render(text, context());
+    assert_eq!(out, Ok("<h1>HELLO WORLD</h1>".to_owned()));
+}
+
+#[test]
+fn include_from_tpl() {
+    let text = r#"{{ define "greeting" }}Hello {{ .name }}{{ end }}
+{{- tpl "{{ include \"greeting\" . }}!" . }}"#;
+    let out = Builder::new().with_all().render(text, context());
+    assert_eq!(out, Ok("Hello World!".to_owned()));
+}
+
+#[test]
+fn include_respects_selected_functions() {
+    let text = r#"{{ define "shout" }}{{ upper .name }}{{ end }}{{ include "shout" . }}"#;
+    let builder = Builder::new().with_templates().with_strings();
+    assert_eq!(builder.render(text, context()), Ok("WORLD".to_owned()));
+    let builder = builder.without("upper");
+    assert!(builder.render(text, context()).is_err());
+}
+
+#[test]
+fn include_missing_template() {
+    let text = r#"{{ include "missing" . }}"#;
+    assert!(Builder::new().with_all().render(text, context()).is_err());
+}